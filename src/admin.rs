@@ -0,0 +1,150 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Admin client - Best-effort queries against the broker admin/metrics endpoint
+
+use anyhow::{bail, Result};
+use hyper::{body, client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::debug;
+
+/// Upper bound for a single admin request so a slow broker never stalls reconcile
+const ADMIN_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cluster-level statistics reported by the broker's admin endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterStats {
+    pub topics: i32,
+    pub partitions: i32,
+    pub under_replicated_partitions: i32,
+}
+
+/// Metadata quorum state reported by the broker's admin endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumStatus {
+    pub established: bool,
+}
+
+/// Cluster metadata: the controller broker and per-broker partition leadership
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterMetadata {
+    pub controller_id: i32,
+    #[serde(default)]
+    pub brokers: Vec<BrokerMetadata>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerMetadata {
+    pub id: i32,
+    pub leader_partitions: i32,
+}
+
+/// State of the partition reassignment started through the admin endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalanceStatus {
+    pub in_progress: bool,
+}
+
+#[derive(Clone)]
+pub struct AdminClient {
+    http: Client<HttpConnector, Body>,
+}
+
+impl AdminClient {
+    pub fn new() -> Self {
+        Self { http: Client::new() }
+    }
+
+    /// Fetch cluster stats through the client Service.
+    ///
+    /// Returns `None` when the endpoint is unreachable, slow or returns
+    /// something unexpected; callers treat the stats as optional.
+    pub async fn cluster_stats(
+        &self,
+        name: &str,
+        namespace: &str,
+        port: i32,
+    ) -> Option<ClusterStats> {
+        let url = format!("http://{}.{}.svc:{}/admin/cluster", name, namespace, port);
+        self.get_json(&url).await
+    }
+
+    /// Fetch the metadata quorum state through the client Service
+    pub async fn quorum_status(
+        &self,
+        name: &str,
+        namespace: &str,
+        port: i32,
+    ) -> Option<QuorumStatus> {
+        let url = format!("http://{}.{}.svc:{}/admin/quorum", name, namespace, port);
+        self.get_json(&url).await
+    }
+
+    /// Fetch the controller and partition leadership through the client Service
+    pub async fn cluster_metadata(
+        &self,
+        name: &str,
+        namespace: &str,
+        port: i32,
+    ) -> Option<ClusterMetadata> {
+        let url = format!("http://{}.{}.svc:{}/admin/metadata", name, namespace, port);
+        self.get_json(&url).await
+    }
+
+    /// Fetch the state of the current or last partition reassignment
+    pub async fn rebalance_status(
+        &self,
+        name: &str,
+        namespace: &str,
+        port: i32,
+    ) -> Option<RebalanceStatus> {
+        let url = format!("http://{}.{}.svc:{}/admin/rebalance", name, namespace, port);
+        self.get_json(&url).await
+    }
+
+    /// Ask the controller to spread partitions evenly across all live brokers
+    pub async fn start_rebalance(
+        &self,
+        name: &str,
+        namespace: &str,
+        port: i32,
+    ) -> Option<RebalanceStatus> {
+        let url = format!("http://{}.{}.svc:{}/admin/rebalance", name, namespace, port);
+        self.request_json(Method::POST, &url).await
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Option<T> {
+        self.request_json(Method::GET, url).await
+    }
+
+    async fn request_json<T: DeserializeOwned>(&self, method: Method, url: &str) -> Option<T> {
+        match timeout(ADMIN_REQUEST_TIMEOUT, self.fetch(method, url)).await {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                debug!(url = %url, error = %e, "Admin endpoint query failed");
+                None
+            }
+            Err(_) => {
+                debug!(url = %url, "Admin endpoint query timed out");
+                None
+            }
+        }
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, method: Method, url: &str) -> Result<T> {
+        let request = Request::builder().method(method).uri(url.parse::<Uri>()?).body(Body::empty())?;
+        let response = self.http.request(request).await?;
+        if !response.status().is_success() {
+            bail!("unexpected status {}", response.status());
+        }
+        let bytes = body::to_bytes(response.into_body()).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
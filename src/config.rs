@@ -0,0 +1,545 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Broker config - Typed model of the broker's config.toml, built from a cluster spec
+
+use crate::crd::{
+    default_data_path, default_kafka_port, default_metrics_path, default_metrics_port, LogDirSpec, QuotaLimits, ShazamqClusterSpec};
+use serde::Serialize;
+
+/// Mount path of the inter-broker TLS Secret
+pub const INTER_BROKER_TLS_DIR: &str = "/etc/shazamq-tls/inter-broker";
+
+/// Mount path of the spec.security.trustBundle ConfigMap or Secret
+pub const TRUST_BUNDLE_DIR: &str = "/etc/shazamq-tls/trust-bundle";
+
+/// Broker container ports, resolved once per reconcile so the Services,
+/// container, probes and config all agree on them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ports {
+    pub kafka: i32,
+    pub metrics: i32,
+    /// Admin API; served by the metrics listener
+    pub admin: i32,
+    pub inter_broker: Option<i32>,
+    /// Whether the metrics listener is on (see `ShazamqClusterSpec::metrics_enabled`)
+    pub metrics_enabled: bool,
+}
+
+impl Ports {
+    pub fn new(spec: &ShazamqClusterSpec) -> Self {
+        let kafka = spec.ports.as_ref().map_or(default_kafka_port(), |p| p.kafka);
+        let metrics = spec.ports.as_ref().map_or(default_metrics_port(), |p| p.metrics);
+        Self {
+            kafka,
+            metrics,
+            admin: metrics,
+            inter_broker: spec.inter_broker().map(|i| i.port),
+            metrics_enabled: spec.metrics_enabled(),
+        }
+    }
+
+    /// Every port declared on the broker container
+    pub fn container_ports(&self) -> Vec<i32> {
+        let mut ports = vec![self.kafka];
+        if self.metrics_enabled {
+            ports.push(self.metrics);
+        }
+        ports.extend(self.inter_broker);
+        ports
+    }
+}
+
+/// The broker's config.toml. Options left unset are omitted from the rendered file.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerConfig {
+    pub broker: BrokerSection,
+    pub storage: StorageSection,
+    pub metrics: MetricsSection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tiered_storage: Option<TieredStorageSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub performance: Option<PerformanceSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networking: Option<NetworkingSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quotas: Option<QuotasSection>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub acls: Vec<AclEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_replica: Option<ReadReplicaSection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BrokerSection {
+    pub host: String,
+    pub port: i32,
+    pub data_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_dirs: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_create_topics: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_partitions: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_replication_factor: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inter_broker: Option<InterBrokerSection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterBrokerSection {
+    pub port: i32,
+    pub protocol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sasl_mechanism: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_bytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_hours: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub retention_overrides: Vec<RetentionOverrideEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionOverrideEntry {
+    pub pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_hours: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retention_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSection {
+    pub enabled: bool,
+    pub host: String,
+    pub port: i32,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TieredStorageSection {
+    pub enabled: bool,
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3: Option<S3Section>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct S3Section {
+    pub bucket: String,
+    pub region: String,
+    pub prefix: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorSection {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<MirrorSourceSection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MirrorSourceSection {
+    pub name: String,
+    pub bootstrap_servers: String,
+    pub security_protocol: String,
+    pub consumer_group_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_consumers: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exactly_once: Option<bool>,
+    pub topic_whitelist: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_blacklist: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_threads: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_threads: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_replica_fetchers: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkingSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_idle_timeout_seconds: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_seconds: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotasSection {
+    #[serde(flatten)]
+    pub defaults: QuotaLimitsSection,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<QuotaOverrideSection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaOverrideSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(flatten)]
+    pub limits: QuotaLimitsSection,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaLimitsSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub producer_byte_rate: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumer_byte_rate: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_percentage: Option<f64>,
+}
+
+impl From<&QuotaLimits> for QuotaLimitsSection {
+    fn from(limits: &QuotaLimits) -> Self {
+        Self {
+            producer_byte_rate: limits.producer_byte_rate,
+            consumer_byte_rate: limits.consumer_byte_rate,
+            request_percentage: limits.request_percentage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AclEntry {
+    pub principal: String,
+    pub resource_type: String,
+    pub resource_name: String,
+    pub operation: String,
+    pub permission: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadReplicaSection {
+    pub enabled: bool,
+    pub leader_eligible: bool,
+}
+
+impl BrokerConfig {
+    /// Render as TOML. The model only holds strings, numbers and tables, so this cannot fail.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("broker config serializes to TOML")
+    }
+}
+
+/// Build the config for one broker group from the cluster spec.
+///
+/// Pure: depends only on its arguments, so the rendered file (and the config hash
+/// derived from it) is stable for a given spec.
+pub fn broker_config(spec: &ShazamqClusterSpec, ports: &Ports, follower_only: bool) -> BrokerConfig {
+    let log_dir_paths: Vec<&str> = log_dirs(spec).iter().map(|d| d.path.as_str()).collect();
+
+    let ca_file = trust_bundle_path(spec);
+    let inter_broker = spec.inter_broker().map(|inter_broker| {
+        let tls = spec.inter_broker_tls_secret().is_some();
+        InterBrokerSection {
+            port: ports.inter_broker.unwrap_or(inter_broker.port),
+            protocol: inter_broker.protocol.clone(),
+            sasl_mechanism: inter_broker.sasl_mechanism.clone(),
+            tls_cert_file: tls.then(|| format!("{}/tls.crt", INTER_BROKER_TLS_DIR)),
+            tls_key_file: tls.then(|| format!("{}/tls.key", INTER_BROKER_TLS_DIR)),
+            ca_file: ca_file.clone().filter(|_| tls),
+        }
+    });
+
+    BrokerConfig {
+        broker: BrokerSection {
+            host: "0.0.0.0".to_string(),
+            port: ports.kafka,
+            data_dir: data_path(spec),
+            log_dirs: (!log_dir_paths.is_empty()).then(|| log_dir_paths.join(",")),
+            compression: spec.compression.clone(),
+            auto_create_topics: spec.auto_create_topics,
+            num_partitions: spec.default_partitions,
+            default_replication_factor: spec.topic_defaults.as_ref().and_then(|t| t.replication_factor),
+            inter_broker,
+        },
+        storage: StorageSection {
+            segment_bytes: spec.storage.as_ref().and_then(|s| s.segment_bytes),
+            retention_hours: spec.storage.as_ref().and_then(|s| s.retention_hours),
+            retention_overrides: spec
+                .retention_overrides
+                .iter()
+                .flatten()
+                .map(|o| RetentionOverrideEntry {
+                    pattern: o.pattern.clone(),
+                    retention_hours: o.retention_hours,
+                    retention_bytes: o.retention_bytes,
+                })
+                .collect(),
+        },
+        metrics: MetricsSection {
+            enabled: ports.metrics_enabled,
+            host: "0.0.0.0".to_string(),
+            port: ports.metrics,
+            path: metrics_path(spec),
+        },
+        tiered_storage: spec.tiered_storage.as_ref().filter(|t| t.enabled).map(|tiered| TieredStorageSection {
+            enabled: true,
+            provider: tiered.provider.clone(),
+            s3: tiered.s3.as_ref().map(|s3| S3Section {
+                bucket: s3.bucket.clone(),
+                region: s3.region.clone(),
+                prefix: s3.prefix.clone(),
+            }),
+        }),
+        mirror: spec.mirror.as_ref().filter(|m| m.enabled).map(|mirror| MirrorSection {
+            enabled: true,
+            sources: mirror
+                .sources
+                .iter()
+                .map(|source| MirrorSourceSection {
+                    name: source.name.clone(),
+                    bootstrap_servers: source.bootstrap_servers.clone(),
+                    security_protocol: source.security_protocol.clone(),
+                    consumer_group_id: source.consumer_group_id.clone(),
+                    num_consumers: source.num_consumers,
+                    exactly_once: source.exactly_once,
+                    topic_whitelist: source.topic_whitelist.clone(),
+                    topic_blacklist: source.topic_blacklist.clone(),
+                    ca_file: ca_file.clone().filter(|_| source.security_protocol.ends_with("SSL")),
+                })
+                .collect(),
+        }),
+        performance: spec.performance.as_ref().map(|performance| PerformanceSection {
+            network_threads: performance.network_threads,
+            io_threads: performance.io_threads,
+            num_replica_fetchers: performance.num_replica_fetchers,
+        }),
+        networking: spec.networking.as_ref().map(|networking| NetworkingSection {
+            max_connections: networking.max_connections,
+            connection_idle_timeout_seconds: networking.connection_idle_timeout_seconds,
+            request_timeout_seconds: networking.request_timeout_seconds,
+        }),
+        quotas: spec.quotas.as_ref().map(|quotas| QuotasSection {
+            defaults: (&quotas.defaults).into(),
+            overrides: quotas
+                .overrides
+                .iter()
+                .flatten()
+                .map(|entry| QuotaOverrideSection {
+                    client_id: entry.client_id.clone(),
+                    user: entry.user.clone(),
+                    limits: (&entry.limits).into(),
+                })
+                .collect(),
+        }),
+        acls: spec
+            .acls
+            .iter()
+            .flatten()
+            .map(|acl| AclEntry {
+                principal: acl.principal.clone(),
+                resource_type: acl.resource_type.clone(),
+                resource_name: acl.resource_name.clone(),
+                operation: acl.operation.clone(),
+                permission: acl.permission.clone(),
+            })
+            .collect(),
+        read_replica: follower_only.then_some(ReadReplicaSection {
+            enabled: true,
+            leader_eligible: false,
+        }),
+    }
+}
+
+/// Path of the trusted CA bundle inside the broker container, if one is configured
+pub fn trust_bundle_path(spec: &ShazamqClusterSpec) -> Option<String> {
+    spec.trust_bundle().map(|t| format!("{}/{}", TRUST_BUNDLE_DIR, t.key))
+}
+
+/// Additional log directories, each backed by its own volume
+pub fn log_dirs(spec: &ShazamqClusterSpec) -> &[LogDirSpec] {
+    spec.storage
+        .as_ref()
+        .and_then(|s| s.log_dirs.as_deref())
+        .unwrap_or_default()
+}
+
+/// Data directory shared by the data VolumeMount and the broker's data_dir
+pub fn data_path(spec: &ShazamqClusterSpec) -> String {
+    spec.storage
+        .as_ref()
+        .map(|s| s.data_path.clone())
+        .unwrap_or_else(default_data_path)
+}
+
+/// HTTP path the broker serves Prometheus metrics on
+pub fn metrics_path(spec: &ShazamqClusterSpec) -> String {
+    spec.monitoring
+        .as_ref()
+        .map(|m| m.path.clone())
+        .unwrap_or_else(default_metrics_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::spec;
+
+    /// config.toml for the primary brokers of a spec given as YAML
+    fn render(yaml: &str) -> String {
+        let spec = spec(yaml);
+        broker_config(&spec, &Ports::new(&spec), false).to_toml()
+    }
+
+    #[test]
+    fn default_ports() {
+        let spec = spec("replicas: 3\n");
+        let ports = Ports::new(&spec);
+        assert_eq!((ports.kafka, ports.metrics, ports.admin, ports.inter_broker), (9092, 9090, 9090, None));
+        assert_eq!(ports.container_ports(), vec![9092, 9090]);
+    }
+
+    #[test]
+    fn admin_api_follows_the_metrics_port() {
+        let spec = spec("replicas: 3\nports:\n  kafka: 19092\n  metrics: 19090\n");
+        let ports = Ports::new(&spec);
+        assert_eq!((ports.kafka, ports.metrics, ports.admin), (19092, 19090, 19090));
+    }
+
+    #[test]
+    fn inter_broker_port_comes_from_security() {
+        let yaml = "replicas: 3\nsecurity:\n  enabled: true\n  interBroker:\n    port: 19093\n";
+        let spec = spec(yaml);
+        let ports = Ports::new(&spec);
+        assert_eq!(ports.inter_broker, Some(19093));
+        assert_eq!(ports.container_ports(), vec![9092, 9090, 19093]);
+        let toml = render(yaml);
+        assert!(toml.contains("[broker.inter_broker]\nport = 19093"), "{}", toml);
+    }
+
+    #[test]
+    fn inter_broker_port_is_ignored_while_security_is_off() {
+        let spec = spec("replicas: 3\nsecurity:\n  enabled: false\n  interBroker:\n    port: 19093\n");
+        assert_eq!(Ports::new(&spec).inter_broker, None);
+    }
+
+    #[test]
+    fn metrics_port_is_not_declared_while_monitoring_is_off() {
+        let spec = spec("replicas: 3\nmonitoring:\n  enabled: false\n");
+        assert_eq!(Ports::new(&spec).container_ports(), vec![9092]);
+    }
+
+    #[test]
+    fn defaults_are_rendered() {
+        let toml = render("replicas: 3\n");
+        assert!(toml.contains("port = 9092"), "{}", toml);
+        assert!(toml.contains("data_dir = \"/data/shazamq\""), "{}", toml);
+        assert!(toml.contains("[metrics]\nenabled = true\nhost = \"0.0.0.0\"\nport = 9090\npath = \"/metrics\""), "{}", toml);
+        assert!(!toml.contains("[read_replica]"), "{}", toml);
+    }
+
+    #[test]
+    fn spec_ports_and_paths_are_rendered() {
+        let toml = render(
+            "replicas: 3\nports:\n  kafka: 19092\n  metrics: 19090\nstorage:\n  dataPath: /var/lib/shazamq\nmonitoring:\n  enabled: true\n  path: /stats\n",
+        );
+        assert!(toml.contains("port = 19092"), "{}", toml);
+        assert!(toml.contains("port = 19090"), "{}", toml);
+        assert!(toml.contains("data_dir = \"/var/lib/shazamq\""), "{}", toml);
+        assert!(toml.contains("path = \"/stats\""), "{}", toml);
+    }
+
+    #[test]
+    fn disabled_monitoring_turns_the_metrics_listener_off() {
+        let toml = render("replicas: 3\nmonitoring:\n  enabled: false\n");
+        assert!(toml.contains("[metrics]\nenabled = false"), "{}", toml);
+    }
+
+    #[test]
+    fn follower_only_groups_are_not_leader_eligible() {
+        let spec = spec("replicas: 3\n");
+        let toml = broker_config(&spec, &Ports::new(&spec), true).to_toml();
+        assert!(toml.contains("[read_replica]\nenabled = true\nleader_eligible = false"), "{}", toml);
+    }
+
+    #[test]
+    fn mirror_topic_blacklist_is_rendered() {
+        let toml = render(
+            "replicas: 3\nmirror:\n  enabled: true\n  sources:\n  - name: east\n    bootstrapServers: east:9092\n    topicBlacklist: [\"internal-.*\", audit]\n",
+        );
+        assert!(toml.contains("[[mirror.sources]]"), "{}", toml);
+        assert!(toml.contains("topic_blacklist = [\"internal-.*\", \"audit\"]"), "{}", toml);
+    }
+
+    #[test]
+    fn mirror_num_consumers_is_rendered() {
+        let toml = render(
+            "replicas: 3\nmirror:\n  enabled: true\n  sources:\n  - name: east\n    bootstrapServers: east:9092\n    numConsumers: 6\n",
+        );
+        assert!(toml.contains("num_consumers = 6"), "{}", toml);
+    }
+
+    #[test]
+    fn unset_mirror_topic_blacklist_is_omitted() {
+        let toml = render(
+            "replicas: 3\nmirror:\n  enabled: true\n  sources:\n  - name: east\n    bootstrapServers: east:9092\n",
+        );
+        assert!(!toml.contains("topic_blacklist"), "{}", toml);
+    }
+
+    #[test]
+    fn quotas_are_rendered() {
+        let toml = render(
+            "replicas: 3\nquotas:\n  producerByteRate: 1048576\n  overrides:\n  - clientId: batch\n    consumerByteRate: 2097152\n    requestPercentage: 50.0\n",
+        );
+        assert!(toml.contains("[quotas]\nproducer_byte_rate = 1048576"), "{}", toml);
+        assert!(
+            toml.contains("[[quotas.overrides]]\nclient_id = \"batch\"\nconsumer_byte_rate = 2097152\nrequest_percentage = 50.0"),
+            "{}",
+            toml
+        );
+    }
+
+    #[test]
+    fn unset_quotas_are_omitted() {
+        assert!(!render("replicas: 3\n").contains("quotas"));
+    }
+
+    #[test]
+    fn performance_tuning_is_rendered() {
+        let toml = render("replicas: 3\nperformance:\n  networkThreads: 8\n  numReplicaFetchers: 4\n");
+        assert!(toml.contains("[performance]\nnetwork_threads = 8\nnum_replica_fetchers = 4"), "{}", toml);
+        assert!(!toml.contains("io_threads"), "{}", toml);
+    }
+
+    #[test]
+    fn topic_replication_factor_is_rendered() {
+        let toml = render("replicas: 3\ntopicDefaults:\n  replicationFactor: 3\n");
+        assert!(toml.contains("default_replication_factor = 3"), "{}", toml);
+    }
+
+    #[test]
+    fn unset_topic_replication_factor_is_omitted() {
+        assert!(!render("replicas: 3\n").contains("default_replication_factor"));
+    }
+}
@@ -5,7 +5,7 @@
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// ShazamqCluster CRD specification
 #[derive(CustomResource, Debug, Clone, Deserialize, Serialize, JsonSchema)]
@@ -21,6 +21,8 @@ use std::collections::BTreeMap;
     printcolumn = r#"{"name":"Replicas", "jsonPath":".spec.replicas", "type":"integer"}"#,
     printcolumn = r#"{"name":"Ready", "jsonPath":".status.readyReplicas", "type":"integer"}"#,
     printcolumn = r#"{"name":"Phase", "jsonPath":".status.phase", "type":"string"}"#,
+    printcolumn = r#"{"name":"Topics", "jsonPath":".status.topicCount", "type":"integer", "priority":1}"#,
+    printcolumn = r#"{"name":"URP", "jsonPath":".status.underReplicatedPartitions", "type":"integer", "priority":1}"#,
     printcolumn = r#"{"name":"Age", "jsonPath":".metadata.creationTimestamp", "type":"date"}"#
 )]
 #[serde(rename_all = "camelCase")]
@@ -28,6 +30,10 @@ pub struct ShazamqClusterSpec {
     /// Number of broker replicas
     pub replicas: i32,
     
+    /// Floor for spec.replicas; below it the brokers keep their live count and the cluster reports Degraded
+    #[serde(default)]
+    pub min_replicas: Option<i32>,
+    
     /// Shazamq version
     #[serde(default = "default_version")]
     pub version: String,
@@ -36,9 +42,17 @@ pub struct ShazamqClusterSpec {
     #[serde(default = "default_image")]
     pub image: String,
     
-    /// Image pull policy
-    #[serde(default = "default_pull_policy")]
-    pub image_pull_policy: String,
+    /// Image pull policy; unset means Always for a mutable tag and IfNotPresent otherwise
+    #[serde(default)]
+    pub image_pull_policy: Option<String>,
+    
+    /// Tags that are re-pushed in place, so pulling them defaults to Always; defaults to `latest`
+    #[serde(default)]
+    pub mutable_image_tags: Option<Vec<String>>,
+    
+    /// Broker log filter passed verbatim as RUST_LOG (e.g. "shazamq::storage=debug,info")
+    #[serde(default)]
+    pub log_filter: Option<String>,
     
     /// Storage configuration
     #[serde(default)]
@@ -72,10 +86,27 @@ pub struct ShazamqClusterSpec {
     #[serde(default)]
     pub node_selector: Option<BTreeMap<String, String>>,
     
+    /// Split the brokers into one StatefulSet per region, each pinned to its own nodes
+    #[serde(default)]
+    pub regions: Option<Vec<RegionSpec>>,
+    
+    /// Mount the ServiceAccount token into broker pods; unset keeps the Kubernetes default (true)
+    #[serde(default)]
+    pub automount_service_account_token: Option<bool>,
+    
+    /// Namespaced kernel parameters set on the broker pods (pod securityContext.sysctls)
+    #[serde(default)]
+    pub sysctls: Option<Vec<SysctlSpec>>,
+    
     /// Service configuration
     #[serde(default)]
     pub service: Option<ServiceConfig>,
     
+    /// Host each broker advertises to clients, e.g. "{pod}.brokers.example.com";
+    /// `{pod}` is the pod name and `{ordinal}` the StatefulSet ordinal
+    #[serde(default)]
+    pub advertised_host_template: Option<String>,
+    
     /// Security configuration
     #[serde(default)]
     pub security: Option<SecurityConfig>,
@@ -83,149 +114,1120 @@ pub struct ShazamqClusterSpec {
     /// Monitoring configuration
     #[serde(default)]
     pub monitoring: Option<MonitoringConfig>,
+    
+    /// StatefulSet update strategy
+    #[serde(default)]
+    pub update_strategy: Option<UpdateStrategyConfig>,
+    
+    /// Spread brokers across topology domains
+    #[serde(default)]
+    pub anti_affinity: Option<AntiAffinityConfig>,
+    
+    /// Topology spread constraints passed through to the broker PodSpec
+    #[serde(default)]
+    pub topology_spread_constraints: Option<Vec<TopologySpreadConstraintSpec>>,
+    
+    /// PodDisruptionBudget limiting voluntary broker evictions
+    #[serde(default)]
+    pub pod_disruption_budget: Option<PodDisruptionBudgetConfig>,
+    
+    /// VerticalPodAutoscaler publishing resource recommendations for the brokers
+    #[serde(default)]
+    pub vpa: Option<VpaConfig>,
+    
+    /// Broker workload kind: StatefulSet, or Deployment for stateless tiered-storage brokers
+    #[serde(default = "default_workload_type")]
+    pub workload_type: String,
+    
+    /// Bundle of defaults for unset fields: "dev" or "prod". `replicas` is required,
+    /// so no profile defaults it; a single-broker dev cluster sets `replicas: 1`.
+    #[serde(default)]
+    pub profile: Option<String>,
+    
+    /// Only report Running once the broker admin endpoint confirms metadata quorum
+    #[serde(default)]
+    pub wait_for_quorum: bool,
+    
+    /// Scale up one broker at a time, adding the next only after every existing
+    /// broker is ready and has joined the cluster (StatefulSet workloads only)
+    #[serde(default)]
+    pub incremental_scale_up: bool,
+    
+    /// After a scale-up, ask the brokers to reassign partitions onto the new ones
+    /// (StatefulSet workloads only)
+    #[serde(default)]
+    pub rebalance_on_scale: bool,
+    
+    /// Revert the broker config when a change sends the brokers into CrashLoopBackOff
+    #[serde(default)]
+    pub auto_rollback: Option<AutoRollbackConfig>,
+    
+    /// Seconds ready replicas may stay below target before a Running cluster reports Updating
+    #[serde(default = "default_phase_debounce_seconds")]
+    pub phase_debounce_seconds: i32,
+    
+    /// Seconds a newly ready broker must stay ready before it counts as available
+    #[serde(default)]
+    pub min_ready_seconds: Option<i32>,
+    
+    /// Broker-side client quotas
+    #[serde(default)]
+    pub quotas: Option<QuotaConfig>,
+    
+    /// Whether producers may create topics implicitly; unset keeps the broker default
+    #[serde(default)]
+    pub auto_create_topics: Option<bool>,
+    
+    /// Partition count of topics created without one; unset keeps the broker default
+    #[serde(default)]
+    pub default_partitions: Option<i32>,
+    
+    /// Defaults for topics created without explicit settings
+    #[serde(default)]
+    pub topic_defaults: Option<TopicDefaults>,
+    
+    /// Broker thread pool sizing
+    #[serde(default)]
+    pub performance: Option<PerformanceConfig>,
+    
+    /// Client connection limits and timeouts
+    #[serde(default)]
+    pub networking: Option<NetworkingConfig>,
+    
+    /// Broker container ports; the Services map their own ports onto these
+    #[serde(default)]
+    pub ports: Option<PortsConfig>,
+    
+    /// Declarative ACLs; requires security.auth to be enabled
+    #[serde(default)]
+    pub acls: Option<Vec<AclSpec>>,
+    
+    /// Retention for topics matching a pattern, overriding the storage defaults
+    #[serde(default)]
+    pub retention_overrides: Option<Vec<RetentionOverride>>,
+    
+    /// Extra init containers run before the broker, with the data and config volumes mounted
+    #[serde(default)]
+    pub init_containers: Option<Vec<InitContainerSpec>>,
+    
+    /// Ingress exposing the broker metrics/admin endpoint
+    #[serde(default)]
+    pub ingress: Option<IngressConfig>,
+    
+    /// Gateway API HTTPRoute exposing the broker metrics/admin endpoint
+    #[serde(default)]
+    pub gateway_route: Option<GatewayRouteConfig>,
+    
+    /// Default compression codec for stored data (none, gzip, snappy, lz4, zstd)
+    #[serde(default)]
+    pub compression: Option<String>,
+    
+    /// Debug-only broker settings such as running under a profiler; off by default
+    #[serde(default)]
+    pub debug: Option<DebugConfig>,
+    
+    /// Memory allocator tuning for the broker process
+    #[serde(default)]
+    pub runtime: Option<RuntimeConfig>,
+    
+    /// postStart/preStop hooks for the broker container
+    #[serde(default)]
+    pub lifecycle: Option<LifecycleConfig>,
+    
+    /// Follower-only brokers that serve reads but never lead partitions
+    #[serde(default)]
+    pub read_replicas: Option<ReadReplicaConfig>,
+    
+    /// Liveness, readiness and startup probes for the broker container
+    #[serde(default)]
+    pub probes: Option<ProbesConfig>,
+}
+
+impl ShazamqClusterSpec {
+    /// Bring specs written against older layouts into the shape the reconciler expects.
+    ///
+    /// Early examples put the tag in `image` (`quay.io/shazamq/shazamq:0.1.0`),
+    /// which would otherwise render as `image:tag:version`; the tag is moved
+    /// into `version`. Nested fields missing from older objects are filled by
+    /// serde defaults matching the CRD schema defaults. Profile defaults are
+    /// applied last.
+    pub fn normalize(&mut self) {
+        self.normalize_image();
+        self.apply_profile();
+    }
+    
+    /// Move a tag given in `image` into `version`
+    pub fn normalize_image(&mut self) {
+        if let Some((repository, tag)) = split_image_tag(&self.image) {
+            self.version = tag;
+            self.image = repository;
+        }
+    }
+    
+    /// Whether `normalize` would change anything, so already-normalized specs need no copy
+    pub fn needs_normalize(&self) -> bool {
+        let profile_pending = match self.profile.as_deref() {
+            Some("prod") => self.anti_affinity.is_none() || self.pod_disruption_budget.is_none(),
+            Some("dev") => {
+                self.storage.as_ref().is_none_or(|s| s.ephemeral.is_none()) || self.replication.is_none()
+            }
+            _ => false,
+        };
+        profile_pending || split_image_tag(&self.image).is_some()
+    }
+    
+    /// Fill fields left unset from `spec.profile`; explicitly set fields always win
+    fn apply_profile(&mut self) {
+        match self.profile.as_deref() {
+            Some("prod") => {
+                self.anti_affinity.get_or_insert_with(|| AntiAffinityConfig {
+                    enabled: true,
+                    required: true,
+                    topology_key: default_topology_key(),
+                });
+                self.pod_disruption_budget.get_or_insert_with(|| PodDisruptionBudgetConfig {
+                    enabled: true,
+                    max_unavailable: default_max_unavailable(),
+                });
+            }
+            // replicas is a required field, so there is no unset value to fill here
+            Some("dev") => {
+                self.storage
+                    .get_or_insert_with(StorageConfig::default)
+                    .ephemeral
+                    .get_or_insert(true);
+                self.replication.get_or_insert(ReplicationConfig {
+                    default_replication_factor: 1,
+                    min_insync_replicas: 1,
+                });
+            }
+            _ => {}
+        }
+    }
+    
+    /// Full image reference for the broker container
+    pub fn image_reference(&self) -> String {
+        // Digest-pinned images are used verbatim
+        if self.image.contains('@') {
+            return self.image.clone();
+        }
+        format!("{}:{}", self.image, self.version)
+    }
+    
+    /// Pull policy for the broker image. Like kubectl, a mutable tag is always
+    /// re-pulled unless the policy is set explicitly.
+    pub fn image_pull_policy(&self) -> String {
+        if let Some(policy) = &self.image_pull_policy {
+            return policy.clone();
+        }
+        let mutable = split_image_tag(&self.image_reference()).is_some_and(|(_, tag)| match &self.mutable_image_tags {
+            Some(tags) => tags.contains(&tag),
+            None => tag == "latest",
+        });
+        if mutable { "Always" } else { "IfNotPresent" }.to_string()
+    }
+    
+    /// Whether brokers run the metrics listener, which also serves the admin API.
+    /// Only an explicit `monitoring.enabled: false` turns it off.
+    pub fn metrics_enabled(&self) -> bool {
+        self.monitoring.as_ref().is_none_or(|m| m.enabled)
+    }
+    
+    /// Inter-broker listener settings, when security is enabled and they're configured
+    pub fn inter_broker(&self) -> Option<&InterBrokerConfig> {
+        self.security.as_ref().filter(|s| s.enabled)?.inter_broker.as_ref()
+    }
+    
+    /// Secret holding the inter-broker TLS certificate, when that listener uses TLS
+    pub fn inter_broker_tls_secret(&self) -> Option<String> {
+        let inter_broker = self.inter_broker()?;
+        if !inter_broker.protocol.ends_with("SSL") {
+            return None;
+        }
+        inter_broker.tls_secret_name.clone().or_else(|| {
+            self.security
+                .as_ref()?
+                .tls
+                .as_ref()
+                .filter(|t| t.enabled)
+                .map(|t| t.secret_name.clone())
+        })
+    }
+    
+    /// CA bundle for outbound TLS; applies whether or not security.enabled is set
+    pub fn trust_bundle(&self) -> Option<&TrustBundleConfig> {
+        self.security.as_ref()?.trust_bundle.as_ref()
+    }
+    
+    /// Config rollback settings, when spec.autoRollback is enabled
+    pub fn auto_rollback(&self) -> Option<&AutoRollbackConfig> {
+        self.auto_rollback.as_ref().filter(|r| r.enabled)
+    }
+    
+    /// Names of the Secrets (in the cluster's namespace) this spec depends on
+    pub fn referenced_secrets(&self) -> BTreeSet<String> {
+        let mut secrets = BTreeSet::new();
+        
+        if let Some(security) = &self.security {
+            if let Some(tls) = security.tls.as_ref().filter(|t| t.enabled) {
+                secrets.insert(tls.secret_name.clone());
+            }
+            if let Some(auth) = security.auth.as_ref().filter(|a| a.enabled) {
+                secrets.insert(auth.secret_name.clone());
+            }
+        }
+        if let Some(secret) = self.inter_broker_tls_secret() {
+            secrets.insert(secret);
+        }
+        if let Some(secret) = self.trust_bundle().and_then(|t| t.secret_name.as_ref()) {
+            secrets.insert(secret.clone());
+        }
+        
+        if let Some(secret) = self
+            .tiered_storage
+            .as_ref()
+            .filter(|t| t.enabled)
+            .and_then(|t| t.s3.as_ref())
+            .and_then(|s3| s3.credentials_secret.as_ref())
+        {
+            secrets.insert(secret.clone());
+        }
+        
+        if let Some(mirror) = self.mirror.as_ref().filter(|m| m.enabled) {
+            for source in &mirror.sources {
+                if let Some(secret) = &source.credentials_secret {
+                    secrets.insert(secret.clone());
+                }
+            }
+        }
+        
+        secrets
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageConfig {
+    /// Size at which the broker rolls a new log segment, in bytes
     pub segment_bytes: Option<i64>,
+    /// Hours a message is kept before it may be deleted
     pub retention_hours: Option<i32>,
+    /// Maximum bytes kept per partition before old segments are deleted
     pub retention_bytes: Option<i64>,
+    
+    /// Extra labels for the broker data PVCs (applied when the StatefulSet is created)
+    pub pvc_labels: Option<BTreeMap<String, String>>,
+    
+    /// Mount path of the data volume, also used as the broker's data_dir
+    #[serde(default = "default_data_path")]
+    pub data_path: String,
+    
+    /// Size of each broker's data volume, e.g. "500Gi" (default 100Gi); claims can
+    /// only be sized at creation, so a smaller value later marks the cluster Degraded
+    pub size: Option<String>,
+    
+    /// Whether data PVCs are deleted when the cluster is deleted or scaled down
+    pub pvc_retention_policy: Option<PvcRetentionPolicy>,
+    
+    /// Additional broker log directories, each on its own volume (set at creation)
+    pub log_dirs: Option<Vec<LogDirSpec>>,
+    
+    /// Keep broker data in an emptyDir instead of a PVC (set at creation; data is lost with the pod)
+    pub ephemeral: Option<bool>,
+    
+    /// Pre-existing PVC mounted as the data volume instead of a volumeClaimTemplate
+    /// (set at creation; single-replica clusters only, since the claim is ReadWriteOnce)
+    pub existing_claim: Option<String>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            segment_bytes: None,
+            retention_hours: None,
+            retention_bytes: None,
+            pvc_labels: None,
+            data_path: default_data_path(),
+            size: None,
+            pvc_retention_policy: None,
+            log_dirs: None,
+            ephemeral: None,
+            existing_claim: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LogDirSpec {
+    /// Volume (and volumeClaimTemplate) name
+    pub name: String,
+    
+    /// Mount path inside the broker container
+    pub path: String,
+    
+    /// Requested storage size, e.g. "500Gi"
+    pub size: String,
+    
+    /// StorageClass for the volume; unset uses the cluster default
+    pub storage_class_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PvcRetentionPolicy {
+    /// Retain or Delete PVCs when the StatefulSet is deleted
+    #[serde(default = "default_retention_action")]
+    pub when_deleted: String,
+    
+    /// Retain or Delete PVCs of brokers removed by a scale-down
+    #[serde(default = "default_retention_action")]
+    pub when_scaled: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TieredStorageConfig {
+    /// Archive closed segments to object storage
     pub enabled: bool,
+    /// Object storage provider (s3 or gcs)
+    #[serde(default = "default_tiered_provider")]
     pub provider: String,
+    /// Hours segments stay on local disk after upload
     pub hot_tier_retention_hours: Option<i32>,
+    /// Bucket settings for the s3 provider
     pub s3: Option<S3Config>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct S3Config {
+    /// Bucket name
     pub bucket: String,
+    /// Bucket region, e.g. `us-east-1`
     pub region: String,
+    /// Key prefix for this cluster's objects
     pub prefix: String,
+    /// Custom S3-compatible endpoint (e.g. MinIO)
     pub endpoint: Option<String>,
+    /// Secret holding `access-key-id` and `secret-access-key`
     pub credentials_secret: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MirrorConfig {
+    /// Mirror topics from the listed source clusters
     pub enabled: bool,
+    /// Source clusters to mirror from
     pub sources: Vec<MirrorSource>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MirrorSource {
+    /// Unique name of the source
     pub name: String,
+    /// Comma-separated `host:port` list of the source cluster
     pub bootstrap_servers: String,
+    /// PLAINTEXT, SSL, SASL_PLAINTEXT or SASL_SSL
+    #[serde(default = "default_security_protocol")]
     pub security_protocol: String,
+    /// SASL mechanism for the SASL protocols
     pub sasl_mechanism: Option<String>,
+    /// Secret holding the source credentials
     pub credentials_secret: Option<String>,
+    /// Topic patterns to mirror
+    #[serde(default = "default_topic_whitelist")]
     pub topic_whitelist: Vec<String>,
+    /// Topic patterns excluded even when whitelisted
     pub topic_blacklist: Option<Vec<String>>,
+    /// Consumer group used on the source cluster
+    #[serde(default = "default_consumer_group_id")]
     pub consumer_group_id: String,
+    /// Parallel consumers per broker; unset keeps the broker default
     pub num_consumers: Option<i32>,
+    /// Commit mirrored records transactionally
     pub exactly_once: Option<bool>,
 }
 
+/// Broker thread pool sizing; unset values keep the broker defaults
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceConfig {
+    /// Threads handling network requests
+    pub network_threads: Option<i32>,
+    /// Threads doing disk I/O
+    pub io_threads: Option<i32>,
+    /// Fetcher threads replicating from each source broker
+    pub num_replica_fetchers: Option<i32>,
+}
+
+/// VerticalPodAutoscaler in recommendation-only mode (updateMode "Off"); it never evicts or resizes brokers
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VpaConfig {
+    /// Create the VerticalPodAutoscaler
+    pub enabled: bool,
+}
+
+/// Broker container ports. The admin API shares the metrics listener. The
+/// inter-broker listener only exists when security.interBroker is set, so its
+/// port lives there with the rest of that listener's settings rather than here,
+/// where a second field could disagree with it.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortsConfig {
+    /// Client listener port
+    #[serde(default = "default_kafka_port")]
+    pub kafka: i32,
+    /// Metrics and admin API port
+    #[serde(default = "default_metrics_port")]
+    pub metrics: i32,
+}
+
+/// Client connection limits and timeouts; unset values keep the broker defaults
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkingConfig {
+    /// Maximum concurrent client connections per broker
+    pub max_connections: Option<i32>,
+    /// Seconds an idle client connection is kept open
+    pub connection_idle_timeout_seconds: Option<i32>,
+    /// Seconds a request may take before the broker fails it
+    pub request_timeout_seconds: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PodDisruptionBudgetConfig {
+    /// Create a PodDisruptionBudget for the brokers
+    pub enabled: bool,
+    /// Brokers that may be down at once during voluntary disruptions
+    #[serde(default = "default_max_unavailable")]
+    pub max_unavailable: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicDefaults {
+    /// Replication factor of topics created without one, between minInsyncReplicas and
+    /// spec.replicas; unset keeps the broker default
+    pub replication_factor: Option<i32>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReplicationConfig {
+    /// Replication factor for automatically created topics
+    #[serde(default = "default_replication_factor")]
     pub default_replication_factor: i32,
+    /// Replicas that must acknowledge a write with acks=all
+    #[serde(default = "default_min_insync_replicas")]
     pub min_insync_replicas: i32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ResourceRequirements {
+    /// Resources reserved for the broker container
     pub requests: Option<ResourceList>,
+    /// Resource caps for the broker container
     pub limits: Option<ResourceList>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ResourceList {
+    /// CPU quantity, e.g. `500m` or `2`
     pub cpu: Option<String>,
+    /// Memory quantity, e.g. `4Gi`
     pub memory: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ServiceConfig {
-    #[serde(rename = "type")]
+    /// Client Service type (ClusterIP, NodePort or LoadBalancer)
+    #[serde(rename = "type", default = "default_service_type")]
     pub service_type: String,
+    /// Client Service port
+    #[serde(default = "default_kafka_port")]
     pub port: i32,
+    /// Metrics Service port
+    #[serde(default = "default_metrics_port")]
     pub metrics_port: i32,
+    
+    /// Public DNS name published through external-dns for the client Service
+    pub external_hostname: Option<String>,
+    
+    /// Create the headless Service; disable when it is managed externally
+    #[serde(default = "default_true")]
+    pub create_headless: bool,
+    
+    /// Headless Service name used by the StatefulSet (defaults to "<name>-headless")
+    pub headless_service_name: Option<String>,
+    
+    /// Publish broker DNS records on the headless Service before the brokers are ready
+    #[serde(default = "default_true")]
+    pub publish_not_ready_addresses: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SecurityConfig {
+    /// Enable the tls, auth and interBroker settings
     pub enabled: bool,
+    /// TLS for client connections
     pub tls: Option<TlsConfig>,
+    /// SASL authentication for client connections
     pub auth: Option<AuthConfig>,
+    /// Dedicated listener for broker-to-broker traffic, secured independently of clients
+    pub inter_broker: Option<InterBrokerConfig>,
+    /// Extra CA certificates trusted for outbound TLS (mirror sources, inter-broker)
+    pub trust_bundle: Option<TrustBundleConfig>,
+}
+
+/// CA bundle from a ConfigMap or Secret; exactly one source must be set
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustBundleConfig {
+    /// ConfigMap holding the bundle
+    pub config_map_name: Option<String>,
+    /// Secret holding the bundle
+    pub secret_name: Option<String>,
+    /// Key holding the PEM-encoded certificates
+    #[serde(default = "default_trust_bundle_key")]
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InterBrokerConfig {
+    /// PLAINTEXT, SSL, SASL_PLAINTEXT or SASL_SSL
+    #[serde(default = "default_security_protocol")]
+    pub protocol: String,
+    /// Required for the SASL protocols
+    pub sasl_mechanism: Option<String>,
+    /// TLS Secret for the listener; defaults to security.tls.secretName
+    pub tls_secret_name: Option<String>,
+    /// Port of the inter-broker listener
+    #[serde(default = "default_inter_broker_port")]
+    pub port: i32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TlsConfig {
+    /// Serve clients over TLS
     pub enabled: bool,
+    /// Secret of type kubernetes.io/tls with the broker certificate
     pub secret_name: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct AuthConfig {
+    /// Require SASL authentication
     pub enabled: bool,
+    /// PLAIN, SCRAM-SHA-256 or SCRAM-SHA-512
     pub mechanism: String,
+    /// Secret holding the user credentials
     pub secret_name: String,
 }
 
+/// A single ACL binding rendered into the broker config
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AclSpec {
+    /// e.g. `User:alice`
+    pub principal: String,
+    /// Topic, Group, Cluster or TransactionalId
+    pub resource_type: String,
+    /// Resource name; `*` matches every resource of the type
+    pub resource_name: String,
+    /// Read, Write, Create, Delete, Alter, Describe, ClusterAction, DescribeConfigs, AlterConfigs, IdempotentWrite or All
+    pub operation: String,
+    /// Allow or Deny
+    #[serde(default = "default_acl_permission")]
+    pub permission: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SysctlSpec {
+    /// e.g. `net.core.somaxconn`
+    pub name: String,
+    /// Value written to the sysctl
+    pub value: String,
+}
+
+/// Ordinals (and so broker ids) of region `i` start at `i * REGION_ORDINAL_STRIDE`
+pub const REGION_ORDINAL_STRIDE: i32 = 1000;
+
+/// A slice of the brokers placed together; its nodeSelector is merged over spec.nodeSelector
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionSpec {
+    /// Suffix of the region's StatefulSet, e.g. `eu-west`
+    pub name: String,
+    /// Brokers in this region; all regions add up to spec.replicas
+    pub replicas: i32,
+    /// Shorthand for a `topology.kubernetes.io/zone` node selector
+    pub zone: Option<String>,
+    /// Node labels the region's brokers must run on
+    pub node_selector: Option<BTreeMap<String, String>>,
+}
+
+/// Retention for the topics whose name matches `pattern`; unset values keep the cluster default
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionOverride {
+    /// Topic name pattern, e.g. `logs.*`
+    pub pattern: String,
+    /// Hours a message is kept in the matching topics
+    pub retention_hours: Option<i32>,
+    /// Maximum bytes kept per partition of the matching topics
+    pub retention_bytes: Option<i64>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitoringConfig {
+    /// Run the broker metrics listener (which also serves the admin API) and report
+    /// cluster stats in status; false removes the metrics port
     pub enabled: bool,
+    /// HTTP path the broker serves metrics on, e.g. `/internal/metrics`
+    #[serde(default = "default_metrics_path")]
+    pub path: String,
+    /// Include the metrics port on the client Service; when false a separate
+    /// `<name>-metrics` Service carries it
+    #[serde(default = "default_true")]
+    pub expose_on_client_service: bool,
+    /// Add `prometheus.io/*` scrape annotations to the broker pods, for Prometheus
+    /// installs that discover pods without the Prometheus operator
+    #[serde(default)]
+    pub annotations: bool,
+    /// Create a ConfigMap holding the bundled Grafana dashboard, labeled for the Grafana dashboard sidecar
+    #[serde(default)]
+    pub dashboard: bool,
+    /// Prometheus operator ServiceMonitor for the metrics port
     pub service_monitor: Option<ServiceMonitorConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ServiceMonitorConfig {
+    /// Create the ServiceMonitor (skipped when its CRD is missing)
     pub enabled: bool,
+    /// Scrape interval, e.g. `30s`
+    #[serde(default = "default_scrape_interval")]
     pub interval: String,
+    /// Scrape timeout, e.g. `10s`
+    #[serde(default = "default_scrape_timeout")]
     pub scrape_timeout: String,
 }
 
-/// Condition for status (compatible with Kubernetes Condition)
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+pub struct UpdateStrategyConfig {
+    /// Rolling update settings for the broker StatefulSet
+    pub rolling_update: Option<RollingUpdateConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingUpdateConfig {
+    /// Only brokers with ordinal >= partition are updated (canary upgrades)
+    pub partition: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AntiAffinityConfig {
+    /// Spread brokers across topology domains
+    pub enabled: bool,
+    
+    /// Hard (required) instead of soft (preferred) anti-affinity
+    #[serde(default)]
+    pub required: bool,
+    
+    /// Node label defining the topology domain (hostname, zone, rack, ...)
+    #[serde(default = "default_topology_key")]
+    pub topology_key: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologySpreadConstraintSpec {
+    /// Maximum allowed difference in broker count between topology domains
+    pub max_skew: i32,
+    
+    /// Node label defining the topology domain (hostname, zone, rack, ...)
+    pub topology_key: String,
+    
+    /// DoNotSchedule or ScheduleAnyway
+    #[serde(default = "default_when_unsatisfiable")]
+    pub when_unsatisfiable: String,
+    
+    /// Labels of the pods counted for the skew; defaults to the broker group's selector
+    #[serde(default)]
+    pub label_selector: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaConfig {
+    /// Cluster-wide defaults applied to every client
+    #[serde(flatten)]
+    pub defaults: QuotaLimits,
+    
+    /// Per-client or per-user overrides of the defaults
+    pub overrides: Option<Vec<QuotaOverride>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaOverride {
+    /// Client id the override applies to
+    pub client_id: Option<String>,
+    /// Authenticated user the override applies to
+    pub user: Option<String>,
+    /// Limits replacing the defaults for this client or user
+    #[serde(flatten)]
+    pub limits: QuotaLimits,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaLimits {
+    /// Produce throughput limit in bytes per second
+    pub producer_byte_rate: Option<i64>,
+    
+    /// Fetch throughput limit in bytes per second
+    pub consumer_byte_rate: Option<i64>,
+    
+    /// Share of broker request-handler time, in percent
+    pub request_percentage: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InitContainerSpec {
+    /// Container name, unique within the broker pod
+    pub name: String,
+    /// Container image
+    pub image: String,
+    /// Always, IfNotPresent or Never
+    pub image_pull_policy: Option<String>,
+    /// Entrypoint override
+    pub command: Option<Vec<String>>,
+    /// Arguments to the entrypoint
+    pub args: Option<Vec<String>>,
+    /// Environment variables
+    pub env: Option<Vec<EnvVarSpec>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct EnvVarSpec {
+    /// Variable name
+    pub name: String,
+    /// Variable value
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IngressConfig {
+    /// Create the Ingress
+    pub enabled: bool,
+    /// IngressClass to use; unset uses the cluster default
+    pub class_name: Option<String>,
+    /// Host the Ingress answers for; required when the Ingress is enabled
+    #[serde(default)]
+    pub host: String,
+    /// Path prefix routed to the metrics/admin port
+    #[serde(default = "default_ingress_path")]
+    pub path: String,
+    /// Extra annotations for the ingress controller
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// Terminate TLS at the Ingress
+    pub tls: Option<IngressTlsConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IngressTlsConfig {
+    /// TLS Secret for the host
+    pub secret_name: String,
+}
+
+/// Gateway API HTTPRoute for the broker metrics/admin endpoint.
+///
+/// Only `allowedPaths` are routed; every other path stays unmatched and is
+/// rejected by the Gateway.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayRouteConfig {
+    /// Create the HTTPRoute (skipped when the Gateway API CRDs are missing)
+    pub enabled: bool,
+    /// Gateways the route attaches to
+    pub parent_refs: Vec<GatewayParentRef>,
+    /// Hostnames the route matches; unset matches the Gateway's
+    pub hostnames: Option<Vec<String>>,
+    /// Path prefixes routed to the metrics/admin port
+    #[serde(default = "default_allowed_paths")]
+    pub allowed_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayParentRef {
+    /// Gateway name
+    pub name: String,
+    /// Gateway namespace; defaults to the cluster's
+    pub namespace: Option<String>,
+    /// Listener of the Gateway to attach to
+    pub section_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugConfig {
+    /// Apply the debug settings
+    pub enabled: bool,
+    
+    /// Profiler command line prepended to the broker command (e.g. ["perf", "record", "-g", "--"])
+    pub profiler_args: Option<Vec<String>>,
+    
+    /// Broker command the profiler wraps; the config arguments are appended by the operator
+    #[serde(default = "default_broker_command")]
+    pub broker_command: Vec<String>,
+    
+    /// Extra environment for the broker, e.g. MALLOC_CONF for heap profiling
+    pub env: Option<Vec<EnvVarSpec>>,
+}
+
+/// Allocator environment for the broker; well-known variables are checked, others are passed through
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeConfig {
+    /// e.g. MALLOC_ARENA_MAX, MALLOC_CONF (jemalloc) or MIMALLOC_* options
+    pub env: Option<Vec<EnvVarSpec>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleConfig {
+    /// Runs right after the broker container starts
+    pub post_start: Option<LifecycleHook>,
+    /// Runs before the broker container is stopped
+    pub pre_stop: Option<LifecycleHook>,
+}
+
+/// A lifecycle hook; exactly one of `exec` or `httpGet` must be set
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleHook {
+    /// Command run inside the broker container
+    pub exec: Option<ExecHook>,
+    /// HTTP request sent to the broker pod
+    pub http_get: Option<HttpGetHook>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ExecHook {
+    /// Command and arguments, run without a shell
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct HttpGetHook {
+    /// Request path
+    pub path: String,
+    /// Container port
+    pub port: i32,
+    /// HTTP or HTTPS; defaults to HTTP
+    pub scheme: Option<String>,
+}
+
+/// Broker container probes; a probe that isn't set is not added to the pod
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbesConfig {
+    /// Restarts the broker when it fails
+    pub liveness: Option<ProbeSpec>,
+    /// Removes the broker from Service endpoints when it fails
+    pub readiness: Option<ProbeSpec>,
+    /// Holds the other probes back until the broker has started
+    pub startup: Option<ProbeSpec>,
+}
+
+/// A probe target; exactly one of `tcpSocket` or `httpGet` must be set.
+/// Unset timings fall back to the Kubernetes defaults, except the startup
+/// probe's failureThreshold which defaults to 30 to cover log recovery.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeSpec {
+    /// Succeeds when the port accepts a connection
+    pub tcp_socket: Option<TcpSocketProbe>,
+    /// Succeeds on a 2xx or 3xx response
+    pub http_get: Option<HttpGetHook>,
+    /// Seconds after start before the first probe
+    pub initial_delay_seconds: Option<i32>,
+    /// Seconds between probes
+    pub period_seconds: Option<i32>,
+    /// Seconds before a probe times out
+    pub timeout_seconds: Option<i32>,
+    /// Must be 1 for liveness and startup probes
+    pub success_threshold: Option<i32>,
+    /// Consecutive failures before the probe is considered failed
+    pub failure_threshold: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TcpSocketProbe {
+    /// Container port
+    pub port: i32,
+}
+
+/// Condition for status (compatible with Kubernetes Condition)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct StatusCondition {
+    /// Condition type, e.g. `ReplicasReady` or `ReconcileError`
     pub r#type: String,
+    /// True, False or Unknown
     pub status: String,
+    /// When status last changed (RFC 3339)
     pub last_transition_time: String,
+    /// Machine-readable reason for the last transition
     pub reason: Option<String>,
+    /// Human-readable details
     pub message: Option<String>,
 }
 
-/// ShazamqCluster status
+/// Follower-only broker group, deployed as a second StatefulSet
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+pub struct ReadReplicaConfig {
+    /// Create the read replica StatefulSet
+    pub enabled: bool,
+    /// Number of follower-only brokers
+    #[serde(default = "default_read_replicas")]
+    pub replicas: i32,
+}
+
+/// Reverts a config change that crash-loops the brokers to the last-known-good config
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoRollbackConfig {
+    /// Watch config changes and roll back ones that crash-loop the brokers
+    pub enabled: bool,
+    /// Seconds after a config change during which a crash loop triggers a rollback
+    #[serde(default = "default_rollback_window_seconds")]
+    pub window_seconds: i32,
+}
+
+/// ShazamqCluster status
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct ShazamqClusterStatus {
+    /// Creating, Updating, Stabilizing or Running
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub phase: Option<String>,
+    /// Desired number of brokers
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub replicas: Option<i32>,
+    /// Brokers whose pods are ready
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ready_replicas: Option<i32>,
+    /// Read replicas whose pods are ready
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_replicas_ready: Option<i32>,
+    /// Version requested in spec.version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_version: Option<String>,
+    /// Version actually running on the current revision of the brokers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_version: Option<String>,
+    /// Current state of the cluster
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub conditions: Option<Vec<StatusCondition>>,
+    /// One entry per broker pod, ordered by id
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub brokers: Option<Vec<BrokerStatus>>,
+    
+    /// Number of topics reported by the broker admin endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic_count: Option<i32>,
+    
+    /// Total partitions across all topics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_count: Option<i32>,
+    
+    /// Partitions whose ISR is smaller than the replication factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub under_replicated_partitions: Option<i32>,
+    
+    /// Client bootstrap addresses (in-cluster and, if configured, external)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoints: Option<Vec<String>>,
+    
+    /// Time of the last successful reconcile (RFC 3339), refreshed at most once per
+    /// steady requeue interval (5 minutes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_reconcile_time: Option<String>,
+    
+    /// SHA-256 of the broker config.toml currently in the ConfigMap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered_config_hash: Option<String>,
+    
+    /// When renderedConfigHash last changed (RFC 3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_config_change_time: Option<String>,
+    
+    /// Broker count partitions were last balanced across (rebalanceOnScale)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rebalanced_replicas: Option<i32>,
+    
+    /// Readiness of each spec.regions entry, in spec order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<Vec<RegionStatus>>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct BrokerStatus {
+    /// Broker id (the pod ordinal)
     pub id: i32,
+    /// Pod name
     pub pod: String,
+    /// Whether the pod is ready
     pub ready: bool,
+    /// Whether this broker is the cluster controller
     pub leader: bool,
+    /// Number of partitions this broker currently leads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leader_partitions: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegionStatus {
+    /// Region name from spec.regions
+    pub name: String,
+    /// Desired brokers in the region
+    pub replicas: i32,
+    /// Ready brokers in the region
+    pub ready_replicas: i32,
+}
+
+/// Split `repo[:port]/path:tag` into repository and tag; digests are left alone
+pub fn split_image_tag(image: &str) -> Option<(String, String)> {
+    if image.contains('@') {
+        return None;
+    }
+    let last_segment_start = image.rfind('/').map_or(0, |i| i + 1);
+    let colon = image[last_segment_start..].rfind(':')? + last_segment_start;
+    Some((image[..colon].to_string(), image[colon + 1..].to_string()))
 }
 
 // Default values
@@ -237,7 +1239,207 @@ fn default_image() -> String {
     "shazamq/shazamq".to_string()
 }
 
-fn default_pull_policy() -> String {
-    "IfNotPresent".to_string()
+fn default_true() -> bool {
+    true
+}
+
+fn default_tiered_provider() -> String {
+    "s3".to_string()
+}
+
+fn default_security_protocol() -> String {
+    "PLAINTEXT".to_string()
+}
+
+fn default_topic_whitelist() -> Vec<String> {
+    vec![".*".to_string()]
+}
+
+fn default_consumer_group_id() -> String {
+    "shazamq-mirror".to_string()
+}
+
+fn default_replication_factor() -> i32 {
+    3
+}
+
+fn default_min_insync_replicas() -> i32 {
+    2
+}
+
+fn default_service_type() -> String {
+    "ClusterIP".to_string()
+}
+
+pub fn default_kafka_port() -> i32 {
+    9092
 }
 
+pub fn default_metrics_port() -> i32 {
+    9090
+}
+
+fn default_scrape_interval() -> String {
+    "30s".to_string()
+}
+
+fn default_scrape_timeout() -> String {
+    "10s".to_string()
+}
+
+fn default_workload_type() -> String {
+    "StatefulSet".to_string()
+}
+
+fn default_inter_broker_port() -> i32 {
+    9093
+}
+
+fn default_max_unavailable() -> i32 {
+    1
+}
+
+fn default_topology_key() -> String {
+    "kubernetes.io/hostname".to_string()
+}
+
+fn default_trust_bundle_key() -> String {
+    "ca.crt".to_string()
+}
+
+fn default_phase_debounce_seconds() -> i32 {
+    30
+}
+
+fn default_when_unsatisfiable() -> String {
+    "DoNotSchedule".to_string()
+}
+
+fn default_broker_command() -> Vec<String> {
+    vec!["shazamq".to_string()]
+}
+
+fn default_ingress_path() -> String {
+    "/".to_string()
+}
+
+pub fn default_metrics_path() -> String {
+    "/metrics".to_string()
+}
+
+fn default_allowed_paths() -> Vec<String> {
+    vec!["/metrics".to_string()]
+}
+
+fn default_retention_action() -> String {
+    "Retain".to_string()
+}
+
+pub fn default_data_path() -> String {
+    "/data/shazamq".to_string()
+}
+
+fn default_acl_permission() -> String {
+    "Allow".to_string()
+}
+
+fn default_read_replicas() -> i32 {
+    1
+}
+
+fn default_rollback_window_seconds() -> i32 {
+    600
+}
+
+/// `v1beta1` of the API, served alongside `v1alpha1` with an identical schema.
+///
+/// `v1alpha1` remains the storage version and the one the operator watches;
+/// conversion is a field-for-field copy until the schemas diverge.
+pub mod v1beta1 {
+    use super::ShazamqClusterStatus;
+    use kube::CustomResource;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    
+    #[derive(CustomResource, Debug, Clone, Deserialize, Serialize, JsonSchema)]
+    #[kube(
+        group = "shazamq.io",
+        version = "v1beta1",
+        kind = "ShazamqCluster",
+        plural = "shazamqclusters",
+        shortname = "sqc",
+        namespaced,
+        status = "ShazamqClusterStatus",
+        printcolumn = r#"{"name":"Version", "jsonPath":".spec.version", "type":"string"}"#,
+        printcolumn = r#"{"name":"Replicas", "jsonPath":".spec.replicas", "type":"integer"}"#,
+        printcolumn = r#"{"name":"Ready", "jsonPath":".status.readyReplicas", "type":"integer"}"#,
+        printcolumn = r#"{"name":"Phase", "jsonPath":".status.phase", "type":"string"}"#,
+        printcolumn = r#"{"name":"Topics", "jsonPath":".status.topicCount", "type":"integer"}"#,
+        printcolumn = r#"{"name":"URP", "jsonPath":".status.underReplicatedPartitions", "type":"integer"}"#,
+        printcolumn = r#"{"name":"Age", "jsonPath":".metadata.creationTimestamp", "type":"date"}"#
+    )]
+    pub struct ShazamqClusterSpec {
+        #[serde(flatten)]
+        pub inner: super::ShazamqClusterSpec,
+    }
+    
+    impl From<super::ShazamqCluster> for ShazamqCluster {
+        fn from(cluster: super::ShazamqCluster) -> Self {
+            Self {
+                metadata: cluster.metadata,
+                spec: ShazamqClusterSpec { inner: cluster.spec },
+                status: cluster.status,
+            }
+        }
+    }
+    
+    impl From<ShazamqCluster> for super::ShazamqCluster {
+        fn from(cluster: ShazamqCluster) -> Self {
+            Self {
+                metadata: cluster.metadata,
+                spec: cluster.spec.inner,
+                status: cluster.status,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use super::*;
+
+    /// A spec from a YAML snippet, normalized the way the reconciler sees it
+    pub fn spec(yaml: &str) -> ShazamqClusterSpec {
+        let mut spec: ShazamqClusterSpec = serde_yaml::from_str(yaml).expect("test spec must deserialize");
+        spec.normalize();
+        spec
+    }
+
+    /// A `demo` cluster in the `messaging` namespace
+    pub fn cluster(yaml: &str) -> ShazamqCluster {
+        let mut cluster = ShazamqCluster::new("demo", spec(yaml));
+        cluster.metadata.namespace = Some("messaging".to_string());
+        cluster
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::spec;
+    
+    #[test]
+    fn dev_profile_fills_unset_fields_but_not_replicas() {
+        let spec = spec("replicas: 3\nprofile: dev\n");
+        assert_eq!(spec.replicas, 3);
+        assert_eq!(spec.storage.as_ref().and_then(|s| s.ephemeral), Some(true));
+        let replication = spec.replication.as_ref().unwrap();
+        assert_eq!((replication.default_replication_factor, replication.min_insync_replicas), (1, 1));
+        assert!(!spec.needs_normalize());
+    }
+    
+    #[test]
+    fn dev_profile_keeps_explicit_fields() {
+        let spec = spec("replicas: 1\nprofile: dev\nstorage:\n  ephemeral: false\n");
+        assert_eq!(spec.storage.as_ref().and_then(|s| s.ephemeral), Some(false));
+    }
+}
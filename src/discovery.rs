@@ -0,0 +1,64 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Discovery - Tracks which optional third-party CRDs are installed in the cluster
+
+use kube::core::GroupVersionKind;
+use kube::{discovery, Client};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use tracing::info;
+
+/// How often discovery is repeated, so CRDs installed later are picked up without a restart
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Optional integrations and whether their CRDs are currently served by the API server
+#[derive(Debug, Default)]
+pub struct Integrations {
+    service_monitor: AtomicBool,
+    http_route: AtomicBool,
+    vertical_pod_autoscaler: AtomicBool,
+}
+
+impl Integrations {
+    /// Prometheus operator ServiceMonitor (monitoring.coreos.com/v1)
+    pub fn service_monitor(&self) -> bool {
+        self.service_monitor.load(Ordering::Relaxed)
+    }
+
+    /// Gateway API HTTPRoute (gateway.networking.k8s.io/v1)
+    pub fn http_route(&self) -> bool {
+        self.http_route.load(Ordering::Relaxed)
+    }
+
+    /// VerticalPodAutoscaler (autoscaling.k8s.io/v1)
+    pub fn vertical_pod_autoscaler(&self) -> bool {
+        self.vertical_pod_autoscaler.load(Ordering::Relaxed)
+    }
+
+    /// Query discovery once and record which integrations are available
+    pub async fn refresh(&self, client: &Client) {
+        let checks = [
+            (&self.service_monitor, GroupVersionKind::gvk("monitoring.coreos.com", "v1", "ServiceMonitor")),
+            (&self.http_route, GroupVersionKind::gvk("gateway.networking.k8s.io", "v1", "HTTPRoute")),
+            (
+                &self.vertical_pod_autoscaler,
+                GroupVersionKind::gvk("autoscaling.k8s.io", "v1", "VerticalPodAutoscaler"),
+            ),
+        ];
+        for (flag, gvk) in checks {
+            let available = discovery::pinned_kind(client, &gvk).await.is_ok();
+            if flag.swap(available, Ordering::Relaxed) != available {
+                info!(group = %gvk.group, kind = %gvk.kind, available = available, "Optional CRD availability changed");
+            }
+        }
+    }
+
+    /// Repeat discovery every REFRESH_INTERVAL until the process exits
+    pub async fn watch(self: Arc<Self>, client: Client) {
+        loop {
+            sleep(REFRESH_INTERVAL).await;
+            self.refresh(&client).await;
+        }
+    }
+}
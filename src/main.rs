@@ -3,20 +3,37 @@
 // Shazamq Operator - Kubernetes Operator for Shazamq Clusters
 
 use futures::StreamExt;
+use k8s_openapi::api::core::v1::Secret;
 use kube::{
-    runtime::{controller::Action, Controller},
+    runtime::{controller::{self, Action}, reflector::ObjectRef, watcher, Controller},
     Api, Client, ResourceExt,
 };
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::time::Duration;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
+mod admin;
+mod config;
 mod crd;
+mod discovery;
+mod metrics;
+#[cfg(feature = "otel")]
+mod otel;
+mod ratelimit;
 mod reconciler;
+mod validation;
+mod webhook;
 
 use crd::ShazamqCluster;
+use discovery::Integrations;
+use metrics::Metrics;
 use reconciler::Reconciler;
 
+/// Reconciles allowed to run at once when RECONCILE_CONCURRENCY is unset; 0 is unbounded
+const DEFAULT_RECONCILE_CONCURRENCY: u16 = 0;
+
 // Custom error type that implements std::error::Error
 #[derive(Debug, thiserror::Error)]
 enum ReconcilerError {
@@ -26,14 +43,52 @@ enum ReconcilerError {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
+    // `shazamq-operator validate <file.yaml>` lints a manifest without a cluster
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("validate") {
+        let Some(path) = args.get(2) else {
+            eprintln!("usage: shazamq-operator validate <file.yaml>");
+            std::process::exit(2);
+        };
+        match validate_file(path) {
+            Ok(()) => {
+                println!("{}: OK", path);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}: {:#}", path, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    // `shazamq-operator crd` prints the CRD generated from crd.rs, doc comments included
+    if args.get(1).map(String::as_str) == Some("crd") {
+        print!("{}", generated_crd()?);
+        return Ok(());
+    }
+    
+    // Initialize tracing (LOG_FORMAT: json | pretty | compact, default json)
+    let log_format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string());
+    let fmt = tracing_subscriber::fmt::layer();
+    let fmt = match log_format.as_str() {
+        "pretty" => fmt.pretty().boxed(),
+        "compact" => fmt.compact().boxed(),
+        _ => fmt.json().boxed(),
+    };
+    let subscriber = tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
-        .json()
-        .init();
+        .with(fmt);
+    // Reconcile spans also go to an OTLP collector when built with `--features otel`
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(otel::layer()?);
+    subscriber.init();
+    if !matches!(log_format.as_str(), "pretty" | "compact" | "json") {
+        warn!(log_format = %log_format, "Unknown LOG_FORMAT, falling back to json");
+    }
 
     info!("╔═══════════════════════════════════════════════════════╗");
     info!("║            Shazamq Kubernetes Operator                ║");
@@ -47,18 +102,90 @@ async fn main() -> anyhow::Result<()> {
     // Create API for ShazamqCluster resources
     let api: Api<ShazamqCluster> = Api::all(client.clone());
     
+    // Serve operator metrics and health probes (METRICS_PORT, default 8080)
+    let metrics = Arc::new(Metrics::default());
+    let metrics_port = std::env::var("METRICS_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8080);
+    tokio::spawn(metrics::serve(metrics.clone(), metrics_port));
+    
+    // Persist spec defaults at admission time (WEBHOOK_CERT_DIR enables, WEBHOOK_PORT default 8443)
+    if let Ok(cert_dir) = std::env::var("WEBHOOK_CERT_DIR") {
+        let webhook_port = std::env::var("WEBHOOK_PORT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(8443);
+        tokio::spawn(webhook::serve(webhook_port, cert_dir.into()));
+    }
+    
+    // Probe for optional CRDs (ServiceMonitor, HTTPRoute, VerticalPodAutoscaler) now and periodically after
+    let integrations = Arc::new(Integrations::default());
+    integrations.refresh(&client).await;
+    info!(
+        service_monitor = integrations.service_monitor(),
+        http_route = integrations.http_route(),
+        vertical_pod_autoscaler = integrations.vertical_pod_autoscaler(),
+        "Discovered optional integrations"
+    );
+    tokio::spawn(integrations.clone().watch(client.clone()));
+    
     // Create reconciler
-    let reconciler = Arc::new(Reconciler::new(client.clone()));
+    let reconciler = Arc::new(Reconciler::new(client.clone(), metrics, integrations));
+    
+    // Reconciles running at once (RECONCILE_CONCURRENCY, 0 for unbounded). One
+    // cluster is never reconciled twice at the same time, and every reconcile
+    // shares the API server write limiter, so raising this can't outrun it.
+    let concurrency = match std::env::var("RECONCILE_CONCURRENCY") {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            warn!(value = %value, "Invalid RECONCILE_CONCURRENCY, falling back to unbounded");
+            DEFAULT_RECONCILE_CONCURRENCY
+        }),
+        Err(_) => DEFAULT_RECONCILE_CONCURRENCY,
+    };
     
-    info!("Starting controller...");
+    info!(concurrency = concurrency, "Starting controller...");
     
     // Start the controller
-    Controller::new(api, Default::default())
+    let controller = Controller::new(api, Default::default())
+        .with_config(controller::Config::default().concurrency(concurrency));
+    
+    // Reconcile a cluster when one of the Secrets its spec references changes
+    let store = controller.store();
+    let secrets: Api<Secret> = Api::all(client.clone());
+    
+    // The stream also ends when watches can't be re-established; only a signal makes that a clean exit
+    let shutdown_requested = Arc::new(AtomicBool::new(false));
+    tokio::spawn({
+        let shutdown_requested = shutdown_requested.clone();
+        async move {
+            shutdown_signal().await;
+            shutdown_requested.store(true, Ordering::SeqCst);
+        }
+    });
+    let last_error: Arc<Mutex<Option<String>>> = Arc::default();
+    
+    controller
+        .watches(secrets, watcher::Config::default(), move |secret| {
+            let secret_name = secret.name_any();
+            let secret_namespace = secret.namespace();
+            store
+                .state()
+                .into_iter()
+                .filter(|cluster| {
+                    cluster.namespace() == secret_namespace
+                        && cluster.spec.referenced_secrets().contains(&secret_name)
+                })
+                .map(|cluster| ObjectRef::from_obj(&*cluster))
+                .collect::<Vec<_>>()
+        })
+        // On SIGTERM/SIGINT stop taking new work and let in-flight reconciles finish
+        .shutdown_on_signal()
         .run(
             move |obj, ctx| {
                 let reconciler = ctx.clone();
                 async move { 
-                    reconciler.reconcile(Arc::try_unwrap(obj).unwrap_or_else(|arc| (*arc).clone())).await
+                    reconciler.reconcile(obj).await
                         .map_err(ReconcilerError::from)
                 }
             },
@@ -73,22 +200,112 @@ async fn main() -> anyhow::Result<()> {
             },
             reconciler,
         )
-        .for_each(|res| async move {
-            match res {
-                Ok((obj, _action)) => {
-                    info!(
-                        name = %obj.name,
-                        namespace = ?obj.namespace,
-                        "Reconciled"
-                    );
-                }
-                Err(e) => {
-                    error!(error = %e, "Controller error");
+        .for_each(|res| {
+            let last_error = last_error.clone();
+            async move {
+                match res {
+                    Ok((obj, _action)) => {
+                        info!(
+                            name = %obj.name,
+                            namespace = ?obj.namespace,
+                            "Reconciled"
+                        );
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Controller error");
+                        *last_error.lock().unwrap() = Some(e.to_string());
+                    }
                 }
             }
         })
         .await;
 
+    if !shutdown_requested.load(Ordering::SeqCst) {
+        let reason = last_error.lock().unwrap().take().unwrap_or_else(|| "no error reported".to_string());
+        error!(last_error = %reason, "Controller stream ended without a shutdown signal");
+        anyhow::bail!("controller stream ended unexpectedly (last error: {})", reason);
+    }
+
+    info!("Controller shut down");
+    #[cfg(feature = "otel")]
+    otel::shutdown();
+
     Ok(())
 }
 
+/// Resolves on SIGINT or SIGTERM, the same signals `shutdown_on_signal` reacts to
+async fn shutdown_signal() {
+    let mut terminate = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            warn!(error = %e, "Failed to listen for SIGTERM");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+/// Both served versions merged into one CRD, v1alpha1 stored
+fn generated_crd() -> anyhow::Result<String> {
+    use kube::core::crd::merge_crds;
+    use kube::CustomResourceExt;
+    
+    let crd = merge_crds(vec![ShazamqCluster::crd(), crd::v1beta1::ShazamqCluster::crd()], "v1alpha1")?;
+    Ok(serde_yaml::to_string(&crd)?)
+}
+
+/// Deserialize a ShazamqCluster manifest and run the same spec validation as the reconciler
+fn validate_file(path: &str) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let manifest: serde_yaml::Value = serde_yaml::from_str(&contents)?;
+    let mut cluster: ShazamqCluster = match manifest.get("apiVersion").and_then(|v| v.as_str()) {
+        Some("shazamq.io/v1beta1") => serde_yaml::from_value::<crd::v1beta1::ShazamqCluster>(manifest)?.into(),
+        _ => serde_yaml::from_value(manifest)?,
+    };
+    cluster.spec.normalize();
+    validation::validate_spec(&cluster.spec)?;
+    match &cluster.metadata.name {
+        Some(name) => validation::validate_name(name, &cluster.spec),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Dotted paths of schema properties without a description
+    fn undescribed(schema: &serde_yaml::Value, path: &str, missing: &mut Vec<String>) {
+        let Some(properties) = schema.get("properties").and_then(|p| p.as_mapping()) else {
+            if let Some(items) = schema.get("items") {
+                undescribed(items, &format!("{}[]", path), missing);
+            }
+            return;
+        };
+        for (key, property) in properties {
+            let path = format!("{}.{}", path, key.as_str().unwrap_or_default());
+            if property.get("description").is_none() {
+                missing.push(path.clone());
+            }
+            undescribed(property, &path, missing);
+        }
+    }
+    
+    #[test]
+    fn generated_crd_describes_every_spec_field() {
+        let crd: serde_yaml::Value = serde_yaml::from_str(&generated_crd().unwrap()).unwrap();
+        let versions = crd["spec"]["versions"].as_sequence().unwrap();
+        assert_eq!(versions.len(), 2);
+        for version in versions {
+            let spec = &version["schema"]["openAPIV3Schema"]["properties"]["spec"];
+            assert!(spec["properties"]["replicas"]["description"].is_string());
+            let mut missing = Vec::new();
+            undescribed(spec, "spec", &mut missing);
+            assert!(missing.is_empty(), "{}: {:?}", version["name"].as_str().unwrap_or_default(), missing);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Metrics - Operator metrics and health endpoints
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{error, info};
+
+/// Operator-level metrics shared between the reconciler and the HTTP server
+#[derive(Default)]
+pub struct Metrics {
+    /// Last successful reconcile per (namespace, name)
+    last_reconcile: Mutex<BTreeMap<(String, String), Instant>>,
+}
+
+impl Metrics {
+    pub fn record_reconcile(&self, namespace: &str, name: &str) {
+        let mut last = self.last_reconcile.lock().unwrap();
+        last.insert((namespace.to_string(), name.to_string()), Instant::now());
+    }
+
+    /// Stop reporting a cluster once it is being deleted
+    pub fn forget_cluster(&self, namespace: &str, name: &str) {
+        let mut last = self.last_reconcile.lock().unwrap();
+        last.remove(&(namespace.to_string(), name.to_string()));
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP shazamq_operator_seconds_since_last_reconcile Seconds since the last successful reconcile of a ShazamqCluster\n");
+        out.push_str("# TYPE shazamq_operator_seconds_since_last_reconcile gauge\n");
+        let last = self.last_reconcile.lock().unwrap();
+        for ((namespace, name), at) in last.iter() {
+            let _ = writeln!(
+                out,
+                "shazamq_operator_seconds_since_last_reconcile{{namespace=\"{}\",name=\"{}\"}} {:.3}",
+                namespace,
+                name,
+                at.elapsed().as_secs_f64()
+            );
+        }
+
+        out
+    }
+}
+
+/// Serve `/metrics`, `/healthz` and `/readyz` until the process exits
+pub async fn serve(metrics: Arc<Metrics>, port: u16) {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+
+    let make_svc = make_service_fn(move |_conn| {
+        let metrics = metrics.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                let metrics = metrics.clone();
+                async move { Ok::<_, Infallible>(handle(&metrics, req)) }
+            }))
+        }
+    });
+
+    info!(port = port, "Serving metrics and health endpoints");
+
+    if let Err(e) = Server::bind(&addr).serve(make_svc).await {
+        error!(error = %e, "Metrics server failed");
+    }
+}
+
+fn handle(metrics: &Metrics, req: Request<Body>) -> Response<Body> {
+    match req.uri().path() {
+        "/metrics" => Response::new(Body::from(metrics.render())),
+        "/healthz" | "/readyz" => Response::new(Body::from("ok")),
+        _ => {
+            let mut response = Response::new(Body::from("not found"));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgotten_clusters_are_no_longer_rendered() {
+        let metrics = Metrics::default();
+        metrics.record_reconcile("messaging", "kept");
+        metrics.record_reconcile("messaging", "deleted");
+
+        metrics.forget_cluster("messaging", "deleted");
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("name=\"kept\""));
+        assert!(!rendered.contains("name=\"deleted\""));
+    }
+}
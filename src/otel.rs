@@ -0,0 +1,33 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Otel - Optional OTLP export of the operator's tracing spans
+
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing::Subscriber;
+use tracing_subscriber::{registry::LookupSpan, Layer};
+
+/// Span export over OTLP/gRPC, only when OTEL_EXPORTER_OTLP_ENDPOINT is set
+pub fn layer<S>() -> anyhow::Result<Option<impl Layer<S>>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") else {
+        return Ok(None);
+    };
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "shazamq-operator".to_string());
+    
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(trace::config().with_resource(Resource::new(vec![KeyValue::new("service.name", service_name)])))
+        .install_batch(runtime::Tokio)?;
+    
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Flush spans still queued in the batch exporter
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
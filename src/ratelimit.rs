@@ -0,0 +1,47 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Rate limiting - Bounds how fast the operator writes to the API server across all clusters
+
+use std::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Leaky-bucket limiter (GCRA): `rate` writes per second on average, with up to
+/// `burst` writes allowed back to back after an idle period
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Time between writes at the sustained rate; None disables limiting
+    interval: Option<Duration>,
+    /// How far ahead of the sustained rate a burst may run
+    tolerance: Duration,
+    /// Theoretical arrival time of the next write
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// A `rate` of 0 disables limiting
+    pub fn new(rate: u32, burst: u32) -> Self {
+        let interval = (rate > 0).then(|| Duration::from_secs(1) / rate);
+        Self {
+            interval,
+            tolerance: interval.unwrap_or_default() * burst.saturating_sub(1),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait until another write is allowed
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let wait = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let start = (*next).max(now);
+            *next = start + interval;
+            start.saturating_duration_since(now + self.tolerance)
+        };
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+}
@@ -2,61 +2,356 @@
 //
 // Reconciler - Handles ShazamqCluster reconciliation logic
 
-use crate::crd::{ShazamqCluster, ShazamqClusterStatus};
-use anyhow::Result;
-use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec};
+use crate::admin::{AdminClient, ClusterMetadata};
+use crate::config::{self, broker_config, Ports, INTER_BROKER_TLS_DIR, TRUST_BUNDLE_DIR};
+use crate::crd::{
+    split_image_tag, AutoRollbackConfig, BrokerStatus, LifecycleHook, LogDirSpec, ProbeSpec, RegionStatus, ShazamqCluster, ShazamqClusterStatus,
+    StatusCondition, REGION_ORDINAL_STRIDE,
+};
+use crate::discovery::Integrations;
+use crate::ratelimit::RateLimiter;
+use crate::metrics::Metrics;
+use crate::validation::{is_safe_sysctl, quantity_value, validate_name, validate_spec};
+use anyhow::{bail, Context, Result};
+use k8s_openapi::api::apps::v1::{
+    Deployment, DeploymentSpec, DeploymentStrategy, RollingUpdateStatefulSetStrategy, StatefulSet, StatefulSetOrdinals,
+    StatefulSetPersistentVolumeClaimRetentionPolicy, StatefulSetSpec, StatefulSetUpdateStrategy,
+};
 use k8s_openapi::api::core::v1::{
-    ConfigMap, Container, ContainerPort, EnvVar, PersistentVolumeClaim, 
-    PersistentVolumeClaimSpec, PodSpec, PodTemplateSpec, ResourceRequirements as K8sResourceRequirements,
-    Service, ServicePort, ServiceSpec, Volume, VolumeMount,
+    Affinity, ConfigMap, ConfigMapVolumeSource, Container, EmptyDirVolumeSource, EnvVarSource, ObjectFieldSelector, Pod, ContainerPort, EnvVar, ExecAction, HTTPGetAction, Lifecycle,
+    LifecycleHandler, PersistentVolumeClaim, 
+    PersistentVolumeClaimSpec, PersistentVolumeClaimVolumeSource, PodAffinityTerm, PodAntiAffinity, PodSecurityContext, PodSpec, PodTemplateSpec, Probe, ResourceRequirements as K8sResourceRequirements,
+    Secret, SecretVolumeSource, Service, ServicePort, ServiceSpec, Sysctl, TCPSocketAction, TopologySpreadConstraint, Volume, VolumeMount, WeightedPodAffinityTerm,
+};
+use k8s_openapi::api::policy::v1::{PodDisruptionBudget, PodDisruptionBudgetSpec};
+use k8s_openapi::api::networking::v1::{
+    HTTPIngressPath, HTTPIngressRuleValue, Ingress, IngressBackend, IngressRule,
+    IngressServiceBackend, IngressSpec, IngressTLS, ServiceBackendPort,
 };
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
 use kube::{
-    api::{Patch, PatchParams},
+    api::{ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, Patch, PatchParams},
     runtime::controller::Action,
-    Api, Client, ResourceExt,
+    Api, Client, Resource, ResourceExt,
 };
+use serde::de::DeserializeOwned;
 use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, info_span, warn, Instrument};
+
+/// Attempts at a status patch before a resourceVersion conflict fails the reconcile
+const STATUS_PATCH_ATTEMPTS: usize = 3;
+
+/// Annotation on a ShazamqCluster that forces a rolling restart when its value changes
+pub const RESTARTED_AT_ANNOTATION: &str = "shazamq.io/restartedAt";
+
+/// Pod and selector label naming the spec.regions entry a broker belongs to
+const REGION_LABEL: &str = "shazamq.io/region";
+
+/// Annotation on a ShazamqCluster that lets it take over an existing, unmanaged StatefulSet of the same name
+pub const ADOPT_ANNOTATION: &str = "shazamq.io/adopt";
+
+/// Annotation on a ShazamqCluster that adds a debug sidecar to the broker pods while set to "true"
+pub const DEBUG_ANNOTATION: &str = "shazamq.io/debug";
+
+/// Debug sidecar image when DEBUG_SIDECAR_IMAGE is unset
+const DEFAULT_DEBUG_SIDECAR_IMAGE: &str = "busybox:1.36";
+
+/// Pod template annotation holding a hash of the rendered config and referenced Secrets
+pub const CONFIG_HASH_ANNOTATION: &str = "shazamq.io/config-hash";
+
+/// ConfigMap annotation holding the config.toml the brokers last ran without crash-looping (spec.autoRollback)
+pub const LAST_GOOD_CONFIG_ANNOTATION: &str = "shazamq.io/last-known-good-config";
+
+/// ConfigMap annotation holding the hash of the rendered config spec.autoRollback reverted
+pub const ROLLED_BACK_CONFIG_ANNOTATION: &str = "shazamq.io/rolled-back-config-hash";
+
+/// Data volume size when spec.storage.size is unset
+const DEFAULT_DATA_SIZE: &str = "100Gi";
+
+/// Startup probe failures tolerated by default; at the default 10s period this
+/// gives a broker five minutes to recover its logs before liveness takes over
+const STARTUP_FAILURE_THRESHOLD: i32 = 30;
+
+/// Grafana dashboard shipped in the `<name>-dashboard` ConfigMap (monitoring.dashboard)
+const GRAFANA_DASHBOARD: &str = include_str!("dashboards/shazamq.json");
+
+/// Prefix of informational pod template annotations summarizing what the operator configured
+const INFO_ANNOTATION_PREFIX: &str = "info.shazamq.io/";
+
+/// Prefix of the per-Secret pod template annotations holding a checksum of the Secret's data
+pub const SECRET_CHECKSUM_ANNOTATION_PREFIX: &str = "checksum.shazamq.io/";
+
+/// Pod template label identifying the config + image revision a broker runs
+pub const REVISION_LABEL: &str = "shazamq.io/revision";
+
+/// Hex digits of the revision hash kept in the label
+const REVISION_LABEL_LEN: usize = 10;
+
+/// Finalizer holding a deleted cluster until its children are torn down in order
+const CLEANUP_FINALIZER: &str = "shazamq.io/cleanup";
+
+/// Requeue interval for a healthy cluster, and the shorter one while it converges
+const STEADY_REQUEUE: Duration = Duration::from_secs(300);
+const CONVERGING_REQUEUE: Duration = Duration::from_secs(15);
+
+/// How often an incremental scale-up checks whether the newest broker has joined
+const SCALE_UP_REQUEUE: Duration = Duration::from_secs(10);
+
+/// How often a deleted cluster is rechecked while its brokers terminate
+const CLEANUP_REQUEUE: Duration = Duration::from_secs(5);
+
+/// Sustained API server writes per second and burst size when APPLY_RATE_LIMIT/APPLY_RATE_BURST are unset
+const DEFAULT_APPLY_RATE_LIMIT: u32 = 50;
+const DEFAULT_APPLY_RATE_BURST: u32 = 100;
 
 pub struct Reconciler {
     client: Client,
+    admin: AdminClient,
+    metrics: Arc<Metrics>,
+    /// Optional CRDs currently installed in the cluster
+    integrations: Arc<Integrations>,
+    /// Registry host that replaces the registry of every image (IMAGE_REGISTRY_OVERRIDE)
+    registry_override: Option<String>,
+    /// Shared by every reconcile so a mass re-reconcile can't flood the API server
+    /// (APPLY_RATE_LIMIT writes/s, APPLY_RATE_BURST)
+    write_limiter: RateLimiter,
+    /// Debug sidecar image, set only when ALLOW_DEBUG_SIDECAR is "true" (DEBUG_SIDECAR_IMAGE)
+    debug_sidecar_image: Option<String>,
 }
 
 impl Reconciler {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, metrics: Arc<Metrics>, integrations: Arc<Integrations>) -> Self {
+        let registry_override = std::env::var("IMAGE_REGISTRY_OVERRIDE")
+            .ok()
+            .map(|r| r.trim_end_matches('/').to_string())
+            .filter(|r| !r.is_empty());
+        if let Some(registry) = &registry_override {
+            info!(registry = %registry, "Relocating all images to the override registry");
+        }
+        
+        let rate = env_u32("APPLY_RATE_LIMIT", DEFAULT_APPLY_RATE_LIMIT);
+        let burst = env_u32("APPLY_RATE_BURST", DEFAULT_APPLY_RATE_BURST);
+        info!(rate = rate, burst = burst, "API server write rate limit");
+        
+        let debug_sidecar_image = (std::env::var("ALLOW_DEBUG_SIDECAR").as_deref() == Ok("true")).then(|| {
+            std::env::var("DEBUG_SIDECAR_IMAGE").unwrap_or_else(|_| DEFAULT_DEBUG_SIDECAR_IMAGE.to_string())
+        });
+        if let Some(image) = &debug_sidecar_image {
+            info!(image = %image, "Debug sidecars allowed via the {} annotation", DEBUG_ANNOTATION);
+        }
+        
+        Self {
+            client,
+            admin: AdminClient::new(),
+            metrics,
+            integrations,
+            registry_override,
+            write_limiter: RateLimiter::new(rate, burst),
+            debug_sidecar_image,
+        }
+    }
+    
+    /// Reconcile one cluster inside a span carrying its identity, a per-pass
+    /// correlation id and, once finished, the outcome
+    pub async fn reconcile(&self, cluster: Arc<ShazamqCluster>) -> Result<Action> {
+        let span = info_span!(
+            "reconcile",
+            name = %cluster.name_any(),
+            namespace = %cluster.namespace().unwrap_or_default(),
+            generation = cluster.metadata.generation.unwrap_or_default(),
+            reconcile_id = %reconcile_id(),
+            outcome = tracing::field::Empty,
+        );
+        
+        let result = self.reconcile_cluster(cluster).instrument(span.clone()).await;
+        match &result {
+            Ok(action) => span.record("outcome", tracing::field::debug(action)),
+            Err(_) => span.record("outcome", "error"),
+        };
+        span.in_scope(|| info!("Reconcile finished"));
+        result
     }
     
-    pub async fn reconcile(&self, cluster: ShazamqCluster) -> Result<Action> {
+    async fn reconcile_cluster(&self, cluster: Arc<ShazamqCluster>) -> Result<Action> {
+        // Migrate older spec layouts before anything reads the spec; the cached
+        // object is only copied when there is something to migrate
+        let cluster = if cluster.spec.needs_normalize() {
+            let mut cluster = ShazamqCluster::clone(&cluster);
+            cluster.spec.normalize();
+            Arc::new(cluster)
+        } else {
+            cluster
+        };
+        
         let name = cluster.name_any();
-        let namespace = cluster.namespace().unwrap_or_else(|| "default".to_string());
+        // The CRD is namespaced, so a missing namespace means a malformed object; falling
+        // back to "default" would put the brokers next to some other team's workloads
+        let Some(namespace) = cluster.namespace() else {
+            bail!("ShazamqCluster {} has no namespace; refusing to create its resources elsewhere", name);
+        };
         
-        info!(
-            name = %name,
-            namespace = %namespace,
-            replicas = cluster.spec.replicas,
-            "Reconciling ShazamqCluster"
-        );
+        if cluster.metadata.deletion_timestamp.is_some() {
+            return self.cleanup(&cluster, &name, &namespace).await;
+        }
+        self.add_finalizer(&cluster, &namespace).await?;
+        
+        info!(replicas = cluster.spec.replicas, "Reconciling ShazamqCluster");
+        
+        match self.reconcile_resources(&cluster, &name, &namespace).await {
+            Ok(action) => Ok(action),
+            Err(e) => {
+                // Make the failure visible on the object; a failing status patch
+                // is only logged so it never masks the original error
+                let message = format!("{:#}", e);
+                let result = self
+                    .set_condition(&cluster, &namespace, "ReconcileError", "True", "ReconcileFailed", &message)
+                    .await;
+                if let Err(status_err) = result {
+                    warn!(name = %name, error = %status_err, "Failed to record reconcile error in status");
+                }
+                Err(e)
+            }
+        }
+    }
+    
+    async fn reconcile_resources(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Action> {
+        // Reject malformed specs before touching any child resources
+        validate_spec(&cluster.spec)?;
+        validate_name(name, &cluster.spec)?;
+        self.check_trust_bundle(cluster, namespace).await?;
+        
+        let mut brokers = BrokerGroup::brokers(cluster, name, self.selector_labels(name));
+        let ports = Ports::new(&cluster.spec);
         
         // Create or update ConfigMap
-        self.reconcile_configmap(&cluster, &name, &namespace).await?;
+        self.reconcile_configmap(cluster, name, namespace, &mut brokers, &ports).await?;
         
         // Create or update Service
-        self.reconcile_service(&cluster, &name, &namespace).await?;
+        self.reconcile_service(cluster, name, namespace, &ports).await?;
         
         // Create or update Headless Service
-        self.reconcile_headless_service(&cluster, &name, &namespace).await?;
+        self.reconcile_headless_service(cluster, name, namespace, &ports).await?;
+        
+        // Create, update or prune the metrics/admin Ingress
+        self.reconcile_ingress(cluster, name, namespace).await?;
+        
+        // Create, update or prune the metrics/admin HTTPRoute
+        self.reconcile_gateway_route(cluster, name, namespace).await?;
+        
+        // Create, update or prune the Prometheus ServiceMonitor
+        self.reconcile_service_monitor(cluster, name, namespace).await?;
+        
+        // Create, update or prune the Grafana dashboard ConfigMap
+        self.reconcile_dashboard(cluster, name, namespace).await?;
+        
+        // Below spec.minReplicas a scale-down is refused: every group keeps its live broker
+        // count and update_status reports Degraded, while everything else still reconciles
+        let floor = cluster.spec.min_replicas.filter(|min| cluster.spec.replicas < *min);
+        let mut target_replicas = cluster.spec.replicas;
+        
+        // Create or update the broker workload, pruning the other kind after a switch
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        let deployments: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+        if cluster.spec.workload_type == "Deployment" {
+            self.delete_managed(&statefulsets, name).await?;
+            if let Some(min) = floor {
+                let live = deployments.get_opt(name).await?.and_then(|d| d.spec).and_then(|s| s.replicas);
+                brokers.replicas = floor_replicas(brokers.replicas, live.or(Some(min)));
+                target_replicas = brokers.replicas;
+            }
+            self.reconcile_deployment(cluster, name, namespace, &brokers, &ports).await?;
+        } else {
+            self.delete_managed(&deployments, name).await?;
+            let mut regions = BrokerGroup::regions(cluster, name, &self.selector_labels(name));
+            for region in &mut regions {
+                region.served_config.clone_from(&brokers.served_config);
+                if floor.is_some() {
+                    let live = statefulsets.get_opt(&region.workload_name).await?.and_then(|s| s.spec).and_then(|s| s.replicas);
+                    region.replicas = floor_replicas(region.replicas, live);
+                }
+            }
+            self.prune_regions(name, &statefulsets, &regions).await?;
+            if regions.is_empty() {
+                if cluster.spec.incremental_scale_up {
+                    brokers.replicas = self.scale_up_step(cluster, name, namespace, &statefulsets).await?;
+                }
+                if let Some(min) = floor {
+                    let live = statefulsets.get_opt(name).await?.and_then(|s| s.spec).and_then(|s| s.replicas);
+                    brokers.replicas = floor_replicas(brokers.replicas, live.or(Some(min)));
+                    target_replicas = brokers.replicas;
+                }
+                self.reconcile_statefulset(cluster, name, namespace, &brokers, &ports).await?;
+            } else if floor.is_some() {
+                target_replicas = regions.iter().map(|r| r.replicas).sum();
+            }
+            for region in &regions {
+                self.reconcile_statefulset(cluster, name, namespace, region, &ports).await?;
+            }
+        }
         
-        // Create or update StatefulSet
-        self.reconcile_statefulset(&cluster, &name, &namespace).await?;
+        // Create, update or prune the PodDisruptionBudget
+        self.reconcile_pod_disruption_budget(cluster, name, namespace).await?;
+        
+        // Create, update or prune the recommendation-only VerticalPodAutoscaler
+        self.reconcile_vpa(cluster, name, namespace).await?;
+        
+        // Create, update or prune the follower-only read replica group
+        self.reconcile_read_replicas(cluster, name, namespace, &ports).await?;
         
         // Update status
-        self.update_status(&cluster, &name, &namespace).await?;
+        let converged = self.update_status(cluster, name, namespace, &ports, target_replicas).await?;
+        
+        self.metrics.record_reconcile(namespace, name);
+        
+        if brokers.replicas < cluster.spec.replicas {
+            return Ok(Action::requeue(SCALE_UP_REQUEUE));
+        }
+        
+        // Nothing watches the brokers themselves, so poll sooner until they are all ready
+        if converged {
+            Ok(Action::requeue(STEADY_REQUEUE))
+        } else {
+            Ok(Action::requeue(CONVERGING_REQUEUE))
+        }
+    }
+    
+    /// Ship the bundled dashboard in a ConfigMap the Grafana sidecar discovers by label
+    async fn reconcile_dashboard(&self, cluster: &ShazamqCluster, name: &str, namespace: &str) -> Result<()> {
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        let dashboard_name = format!("{}-dashboard", name);
+        
+        if !cluster.spec.monitoring.as_ref().is_some_and(|m| m.enabled && m.dashboard) {
+            return self.delete_managed(&api, &dashboard_name).await;
+        }
+        
+        let mut labels = self.common_labels(name);
+        labels.insert("grafana_dashboard".to_string(), "1".to_string());
+        let configmap = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(dashboard_name.clone()),
+                namespace: Some(namespace.to_string()),
+                labels: Some(labels),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([("shazamq.json".to_string(), GRAFANA_DASHBOARD.to_string())])),
+            ..Default::default()
+        };
+        
+        let pp = PatchParams::apply("shazamq-operator");
+        self.write_limiter.acquire().await;
+        api.patch(&dashboard_name, &pp, &Patch::Apply(&configmap)).await?;
+        
+        info!(name = %dashboard_name, "Dashboard ConfigMap reconciled");
         
-        // Requeue after 5 minutes to check health
-        Ok(Action::requeue(Duration::from_secs(300)))
+        Ok(())
     }
     
     async fn reconcile_configmap(
@@ -64,20 +359,34 @@ impl Reconciler {
         cluster: &ShazamqCluster,
         name: &str,
         namespace: &str,
+        group: &mut BrokerGroup,
+        ports: &Ports,
     ) -> Result<()> {
         let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
         
         let mut config_data = BTreeMap::new();
         
         // Generate TOML configuration
-        let config_toml = self.generate_config_toml(cluster);
+        let mut config_toml = self.group_config_toml(cluster, group, ports);
+        let mut annotations = None;
+        if let Some(rollback) = cluster.spec.auto_rollback().filter(|_| !group.follower_only) {
+            let existing = api.get_opt(&group.config_name).await?;
+            let (served, guard) = self
+                .guard_config(cluster, name, namespace, existing.as_ref(), config_toml, rollback)
+                .await?;
+            // The pod template hashes what is served, so a rollback also rolls the brokers back
+            group.served_config = Some(served.clone());
+            config_toml = served;
+            annotations = Some(guard);
+        }
         config_data.insert("config.toml".to_string(), config_toml);
         
         let configmap = ConfigMap {
             metadata: ObjectMeta {
-                name: Some(format!("{}-config", name)),
+                name: Some(group.config_name.clone()),
                 namespace: Some(namespace.to_string()),
                 labels: Some(self.common_labels(name)),
+                annotations,
                 ..Default::default()
             },
             data: Some(config_data),
@@ -87,18 +396,99 @@ impl Reconciler {
         let pp = PatchParams::apply("shazamq-operator");
         let patch = Patch::Apply(&configmap);
         
-        api.patch(&format!("{}-config", name), &pp, &patch).await?;
+        self.write_limiter.acquire().await;
+        api.patch(&group.config_name, &pp, &patch).await?;
         
-        info!(name = %name, "ConfigMap reconciled");
+        info!(name = %group.config_name, "ConfigMap reconciled");
         
         Ok(())
     }
     
+    /// Pick the config to serve under spec.autoRollback, with the ConfigMap annotations tracking it.
+    ///
+    /// While a config change is younger than the rollback window and a broker is in
+    /// CrashLoopBackOff, the last-known-good config is served instead and the rendered
+    /// config's hash is recorded as rolled back. It stays rolled back until the rendered
+    /// config changes again, so the operator never flips between the two. The config
+    /// being replaced only becomes the last-known-good one if the cluster was Running on it.
+    async fn guard_config(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        existing: Option<&ConfigMap>,
+        rendered: String,
+        rollback: &AutoRollbackConfig,
+    ) -> Result<(String, BTreeMap<String, String>)> {
+        let existing_annotations = existing.and_then(|c| c.metadata.annotations.as_ref());
+        let current = existing.and_then(|c| c.data.as_ref()).and_then(|d| d.get("config.toml"));
+        let mut last_good = existing_annotations.and_then(|a| a.get(LAST_GOOD_CONFIG_ANNOTATION)).cloned();
+        let rendered_hash = sha256_hex(rendered.as_bytes());
+        let status = cluster.status.as_ref();
+        
+        let mut annotations = BTreeMap::new();
+        let mut served = rendered;
+        if existing_annotations.and_then(|a| a.get(ROLLED_BACK_CONFIG_ANNOTATION)) == Some(&rendered_hash) {
+            if let Some(good) = &last_good {
+                served = good.clone();
+            }
+            annotations.insert(ROLLED_BACK_CONFIG_ANNOTATION.to_string(), rendered_hash);
+        } else if current != Some(&served) {
+            if status.and_then(|s| s.phase.as_deref()) == Some("Running") {
+                last_good = current.cloned();
+            }
+        } else if let Some(good) = last_good.clone().filter(|good| *good != served) {
+            // The window runs from the change status recorded for this very config
+            let changed_at = status
+                .filter(|s| s.rendered_config_hash.as_ref() == Some(&rendered_hash))
+                .and_then(|s| s.last_config_change_time.as_deref())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+            let within_window = changed_at.is_some_and(|changed| {
+                chrono::Utc::now().signed_duration_since(changed)
+                    < chrono::Duration::seconds(rollback.window_seconds.into())
+            });
+            if within_window {
+                if let Some(pod) = self.crash_looping_broker(name, namespace).await? {
+                    warn!(name = %name, pod = %pod, "Broker crash-looping after a config change, rolling back to the last-known-good config");
+                    served = good;
+                    annotations.insert(ROLLED_BACK_CONFIG_ANNOTATION.to_string(), rendered_hash);
+                }
+            }
+        }
+        if let Some(good) = last_good {
+            annotations.insert(LAST_GOOD_CONFIG_ANNOTATION.to_string(), good);
+        }
+        
+        Ok((served, annotations))
+    }
+    
+    /// Name of a broker pod with a container in CrashLoopBackOff, if any
+    async fn crash_looping_broker(&self, name: &str, namespace: &str) -> Result<Option<String>> {
+        let api: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let selector = label_selector(&self.selector_labels(name));
+        
+        let pods = api.list(&ListParams::default().labels(&selector)).await?;
+        let crash_looping = pods.into_iter().find(|pod| {
+            pod.status
+                .as_ref()
+                .and_then(|s| s.container_statuses.as_ref())
+                .into_iter()
+                .flatten()
+                .any(|c| {
+                    c.state.as_ref().and_then(|s| s.waiting.as_ref()).and_then(|w| w.reason.as_deref())
+                        == Some("CrashLoopBackOff")
+                })
+        });
+        
+        Ok(crash_looping.map(|pod| pod.name_any()))
+    }
+    
     async fn reconcile_service(
         &self,
         cluster: &ShazamqCluster,
         name: &str,
         namespace: &str,
+        ports: &Ports,
     ) -> Result<()> {
         let api: Api<Service> = Api::namespaced(self.client.clone(), namespace);
         
@@ -109,30 +499,45 @@ impl Reconciler {
         let port = service_config.map(|s| s.port).unwrap_or(9092);
         let metrics_port = service_config.map(|s| s.metrics_port).unwrap_or(9090);
         
+        let annotations = service_config
+            .and_then(|s| s.external_hostname.as_ref())
+            .map(|hostname| {
+                let mut annotations = BTreeMap::new();
+                annotations.insert(
+                    "external-dns.alpha.kubernetes.io/hostname".to_string(),
+                    hostname.clone(),
+                );
+                annotations
+            });
+        
+        let metrics_service_port = ServicePort {
+            name: Some("metrics".to_string()),
+            port: metrics_port,
+            target_port: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(ports.metrics)),
+            ..Default::default()
+        };
+        let mut service_ports = vec![ServicePort {
+            name: Some("kafka".to_string()),
+            port,
+            target_port: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(ports.kafka)),
+            ..Default::default()
+        }];
+        if ports.metrics_enabled && metrics_on_client_service(cluster) {
+            service_ports.push(metrics_service_port.clone());
+        }
+        
         let service = Service {
             metadata: ObjectMeta {
                 name: Some(name.to_string()),
                 namespace: Some(namespace.to_string()),
                 labels: Some(self.common_labels(name)),
+                annotations,
                 ..Default::default()
             },
             spec: Some(ServiceSpec {
                 type_: Some(service_type),
                 selector: Some(self.selector_labels(name)),
-                ports: Some(vec![
-                    ServicePort {
-                        name: Some("kafka".to_string()),
-                        port,
-                        target_port: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(9092)),
-                        ..Default::default()
-                    },
-                    ServicePort {
-                        name: Some("metrics".to_string()),
-                        port: metrics_port,
-                        target_port: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(9090)),
-                        ..Default::default()
-                    },
-                ]),
+                ports: Some(service_ports),
                 ..Default::default()
             }),
             ..Default::default()
@@ -141,312 +546,1915 @@ impl Reconciler {
         let pp = PatchParams::apply("shazamq-operator");
         let patch = Patch::Apply(&service);
         
+        self.write_limiter.acquire().await;
         api.patch(name, &pp, &patch).await?;
         
         info!(name = %name, "Service reconciled");
         
-        Ok(())
-    }
-    
-    async fn reconcile_headless_service(
-        &self,
-        _cluster: &ShazamqCluster,
-        name: &str,
-        namespace: &str,
-    ) -> Result<()> {
-        let api: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+        // Without the port on the client Service, metrics get a ClusterIP Service of their own
+        let metrics_name = format!("{}-metrics", name);
+        if !ports.metrics_enabled || metrics_on_client_service(cluster) {
+            return self.delete_managed(&api, &metrics_name).await;
+        }
         
-        let service = Service {
+        let metrics_service = Service {
             metadata: ObjectMeta {
-                name: Some(format!("{}-headless", name)),
+                name: Some(metrics_name.clone()),
                 namespace: Some(namespace.to_string()),
                 labels: Some(self.common_labels(name)),
                 ..Default::default()
             },
             spec: Some(ServiceSpec {
-                cluster_ip: Some("None".to_string()),
+                type_: Some("ClusterIP".to_string()),
                 selector: Some(self.selector_labels(name)),
-                ports: Some(vec![
-                    ServicePort {
-                        name: Some("kafka".to_string()),
-                        port: 9092,
-                        ..Default::default()
-                    },
-                ]),
+                ports: Some(vec![metrics_service_port]),
                 ..Default::default()
             }),
             ..Default::default()
         };
         
-        let pp = PatchParams::apply("shazamq-operator");
-        let patch = Patch::Apply(&service);
-        
-        api.patch(&format!("{}-headless", name), &pp, &patch).await?;
+        self.write_limiter.acquire().await;
+        api.patch(&metrics_name, &pp, &Patch::Apply(&metrics_service)).await?;
         
-        info!(name = %name, "Headless service reconciled");
+        info!(name = %metrics_name, "Metrics Service reconciled");
         
         Ok(())
     }
     
-    async fn reconcile_statefulset(
+    async fn reconcile_headless_service(
         &self,
         cluster: &ShazamqCluster,
         name: &str,
         namespace: &str,
+        ports: &Ports,
     ) -> Result<()> {
-        let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
-        
-        let replicas = cluster.spec.replicas;
-        let version = &cluster.spec.version;
-        let image = format!("{}:{}", cluster.spec.image, version);
-        
-        // Build container
-        let mut env_vars = vec![
-            EnvVar {
-                name: "RUST_LOG".to_string(),
-                value: Some("info".to_string()),
-                ..Default::default()
-            },
-        ];
+        let api: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+        let headless_name = headless_service_name(cluster, name);
         
-        // Add mirror configuration if enabled
-        if let Some(mirror) = &cluster.spec.mirror {
-            if mirror.enabled {
-                env_vars.push(EnvVar {
-                    name: "SHAZAMQ_MIRROR_ENABLED".to_string(),
-                    value: Some("true".to_string()),
-                    ..Default::default()
-                });
+        // The headless Service may be managed externally (e.g. by a service mesh); it may
+        // not exist yet, but a Service with a cluster IP would break per-broker DNS
+        if cluster.spec.service.as_ref().is_some_and(|s| !s.create_headless) {
+            match api.get_opt(&headless_name).await? {
+                None => warn!(service = %headless_name, "External headless Service not found; broker DNS names won't resolve until it exists"),
+                Some(service) if service.spec.as_ref().and_then(|s| s.cluster_ip.as_deref()) != Some("None") => bail!(
+                    "spec.service.headlessServiceName: Service \"{}\" is not headless (clusterIP must be None)",
+                    headless_name
+                ),
+                Some(service) if service.spec.as_ref().and_then(|s| s.publish_not_ready_addresses) != Some(true) => warn!(
+                    service = %headless_name,
+                    "External headless Service doesn't publish not-ready addresses; starting brokers may not resolve each other"
+                ),
+                Some(_) => {}
             }
+            return Ok(());
         }
         
-        let container = Container {
-            name: "shazamq".to_string(),
-            image: Some(image.clone()),
-            image_pull_policy: Some(cluster.spec.image_pull_policy.clone()),
-            ports: Some(vec![
-                ContainerPort {
-                    name: Some("kafka".to_string()),
-                    container_port: 9092,
-                    ..Default::default()
-                },
-                ContainerPort {
-                    name: Some("metrics".to_string()),
-                    container_port: 9090,
-                    ..Default::default()
-                },
-            ]),
-            env: Some(env_vars),
-            volume_mounts: Some(vec![
-                VolumeMount {
-                    name: "data".to_string(),
-                    mount_path: "/data/shazamq".to_string(),
-                    ..Default::default()
-                },
-                VolumeMount {
-                    name: "config".to_string(),
-                    mount_path: "/etc/shazamq".to_string(),
-                    ..Default::default()
-                },
-            ]),
-            args: Some(vec![
-                "--config".to_string(),
-                "/etc/shazamq/config.toml".to_string(),
-            ]),
-            ..Default::default()
-        };
+        let service = self.headless_service(cluster, name, namespace, ports);
         
-        let mut pod_labels = self.selector_labels(name);
-        if let Some(labels) = &cluster.spec.pod_labels {
-            pod_labels.extend(labels.clone());
-        }
+        let pp = PatchParams::apply("shazamq-operator");
+        let patch = Patch::Apply(&service);
         
-        let pod_template = PodTemplateSpec {
-            metadata: Some(ObjectMeta {
-                labels: Some(pod_labels),
-                annotations: cluster.spec.pod_annotations.clone(),
-                ..Default::default()
-            }),
-            spec: Some(PodSpec {
-                containers: vec![container],
-                volumes: Some(vec![
-                    Volume {
-                        name: "config".to_string(),
-                        config_map: Some(k8s_openapi::api::core::v1::ConfigMapVolumeSource {
-                            name: Some(format!("{}-config", name)),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                ]),
-                node_selector: cluster.spec.node_selector.clone(),
-                ..Default::default()
-            }),
+        self.write_limiter.acquire().await;
+        api.patch(&headless_name, &pp, &patch).await?;
+        
+        info!(name = %name, "Headless service reconciled");
+        
+        Ok(())
+    }
+    
+    async fn reconcile_ingress(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+    ) -> Result<()> {
+        let api: Api<Ingress> = Api::namespaced(self.client.clone(), namespace);
+        let ingress_name = format!("{}-admin", name);
+        
+        let Some(config) = cluster.spec.ingress.as_ref().filter(|i| i.enabled) else {
+            return self.delete_managed(&api, &ingress_name).await;
         };
         
-        let statefulset = StatefulSet {
+        let ingress = Ingress {
             metadata: ObjectMeta {
-                name: Some(name.to_string()),
+                name: Some(ingress_name.clone()),
                 namespace: Some(namespace.to_string()),
                 labels: Some(self.common_labels(name)),
+                annotations: config.annotations.clone(),
                 ..Default::default()
             },
-            spec: Some(StatefulSetSpec {
-                replicas: Some(replicas),
-                selector: LabelSelector {
-                    match_labels: Some(self.selector_labels(name)),
-                    ..Default::default()
-                },
-                template: pod_template,
-                service_name: format!("{}-headless", name),
-                volume_claim_templates: Some(vec![
-                    PersistentVolumeClaim {
-                        metadata: ObjectMeta {
-                            name: Some("data".to_string()),
-                            ..Default::default()
-                        },
-                        spec: Some(PersistentVolumeClaimSpec {
-                            access_modes: Some(vec!["ReadWriteOnce".to_string()]),
-                            resources: Some(K8sResourceRequirements {
-                                requests: Some({
-                                    let mut map = BTreeMap::new();
-                                    map.insert(
-                                        "storage".to_string(),
-                                        k8s_openapi::apimachinery::pkg::api::resource::Quantity("100Gi".to_string()),
-                                    );
-                                    map
+            spec: Some(IngressSpec {
+                ingress_class_name: config.class_name.clone(),
+                rules: Some(vec![IngressRule {
+                    host: Some(config.host.clone()),
+                    http: Some(HTTPIngressRuleValue {
+                        paths: vec![HTTPIngressPath {
+                            path: Some(config.path.clone()),
+                            path_type: "Prefix".to_string(),
+                            backend: IngressBackend {
+                                service: Some(IngressServiceBackend {
+                                    name: metrics_service_name(cluster, name),
+                                    port: Some(ServiceBackendPort {
+                                        name: Some("metrics".to_string()),
+                                        ..Default::default()
+                                    }),
                                 }),
                                 ..Default::default()
-                            }),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                ]),
+                            },
+                        }],
+                    }),
+                }]),
+                tls: config.tls.as_ref().map(|tls| {
+                    vec![IngressTLS {
+                        hosts: Some(vec![config.host.clone()]),
+                        secret_name: Some(tls.secret_name.clone()),
+                    }]
+                }),
                 ..Default::default()
             }),
             ..Default::default()
         };
         
         let pp = PatchParams::apply("shazamq-operator");
-        let patch = Patch::Apply(&statefulset);
+        let patch = Patch::Apply(&ingress);
         
-        api.patch(name, &pp, &patch).await?;
+        self.write_limiter.acquire().await;
+        api.patch(&ingress_name, &pp, &patch).await?;
         
-        info!(name = %name, replicas = replicas, "StatefulSet reconciled");
+        info!(name = %name, "Ingress reconciled");
         
         Ok(())
     }
     
-    async fn update_status(
+    /// Apply an HTTPRoute (gateway.networking.k8s.io/v1) for the metrics/admin
+    /// endpoint. Gateway API CRDs are optional, so their absence is not an error.
+    async fn reconcile_gateway_route(
         &self,
         cluster: &ShazamqCluster,
         name: &str,
         namespace: &str,
     ) -> Result<()> {
-        let api: Api<ShazamqCluster> = Api::namespaced(self.client.clone(), namespace);
+        let resource = ApiResource::from_gvk_with_plural(
+            &GroupVersionKind::gvk("gateway.networking.k8s.io", "v1", "HTTPRoute"),
+            "httproutes",
+        );
+        let api: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), namespace, &resource);
+        let route_name = format!("{}-admin", name);
         
-        // Get current StatefulSet
-        let sts_api: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
-        let sts = sts_api.get(name).await?;
+        if !self.integrations.http_route() {
+            if cluster.spec.gateway_route.as_ref().is_some_and(|r| r.enabled) {
+                info!(name = %name, "Gateway API CRDs not installed, skipping HTTPRoute");
+            }
+            return Ok(());
+        }
         
-        let ready_replicas = sts.status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0);
-        let replicas = cluster.spec.replicas;
+        let result = match cluster.spec.gateway_route.as_ref().filter(|r| r.enabled) {
+            None => self.delete_managed(&api, &route_name).await,
+            Some(config) => {
+                let parent_refs: Vec<_> = config
+                    .parent_refs
+                    .iter()
+                    .map(|p| {
+                        let mut parent = serde_json::json!({ "name": p.name });
+                        if let Some(ns) = &p.namespace {
+                            parent["namespace"] = ns.clone().into();
+                        }
+                        if let Some(section) = &p.section_name {
+                            parent["sectionName"] = section.clone().into();
+                        }
+                        parent
+                    })
+                    .collect();
+                // One PathPrefix match per allowed path; anything else has no route
+                let matches: Vec<_> = config
+                    .allowed_paths
+                    .iter()
+                    .map(|path| serde_json::json!({ "path": { "type": "PathPrefix", "value": path } }))
+                    .collect();
+                
+                let mut route = DynamicObject::new(&route_name, &resource).within(namespace);
+                route.metadata.labels = Some(self.common_labels(name));
+                route.data = serde_json::json!({
+                    "spec": {
+                        "parentRefs": parent_refs,
+                        "rules": [{
+                            "matches": matches,
+                            "backendRefs": [{
+                                "name": metrics_service_name(cluster, name),
+                                "port": cluster.spec.service.as_ref().map(|s| s.metrics_port).unwrap_or(9090),
+                            }],
+                        }],
+                    }
+                });
+                if let Some(hostnames) = &config.hostnames {
+                    route.data["spec"]["hostnames"] = serde_json::json!(hostnames);
+                }
+                
+                let pp = PatchParams::apply("shazamq-operator");
+                self.write_limiter.acquire().await;
+                api.patch(&route_name, &pp, &Patch::Apply(&route))
+                    .await
+                    .map(|_| info!(name = %name, "HTTPRoute reconciled"))
+                    .map_err(Into::into)
+            }
+        };
         
-        let phase = if ready_replicas == replicas {
-            "Running"
-        } else if ready_replicas > 0 {
-            "Updating"
-        } else {
+        match result {
+            Err(e) if is_not_found(&e) => {
+                info!(name = %name, "Gateway API CRDs not installed, skipping HTTPRoute");
+                Ok(())
+            }
+            other => other,
+        }
+    }
+    
+    /// Apply a ServiceMonitor (monitoring.coreos.com/v1) scraping the client
+    /// Service. The Prometheus operator is optional, so its absence is not an error.
+    async fn reconcile_service_monitor(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+    ) -> Result<()> {
+        let resource = ApiResource::from_gvk_with_plural(
+            &GroupVersionKind::gvk("monitoring.coreos.com", "v1", "ServiceMonitor"),
+            "servicemonitors",
+        );
+        let api: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), namespace, &resource);
+        
+        let config = cluster
+            .spec
+            .monitoring
+            .as_ref()
+            .filter(|m| m.enabled)
+            .and_then(|m| m.service_monitor.as_ref())
+            .filter(|s| s.enabled);
+        
+        if !self.integrations.service_monitor() {
+            if config.is_some() {
+                info!(name = %name, "Prometheus operator CRDs not installed, skipping ServiceMonitor");
+            }
+            return Ok(());
+        }
+        
+        let result = match config {
+            None => self.delete_managed(&api, name).await,
+            Some(config) => {
+                let mut monitor = DynamicObject::new(name, &resource).within(namespace);
+                monitor.metadata.labels = Some(self.common_labels(name));
+                monitor.data = serde_json::json!({
+                    "spec": {
+                        "selector": { "matchLabels": self.common_labels(name) },
+                        "endpoints": [{
+                            "port": "metrics",
+                            "path": metrics_path(cluster),
+                            "interval": config.interval,
+                            "scrapeTimeout": config.scrape_timeout,
+                        }],
+                    }
+                });
+                
+                let pp = PatchParams::apply("shazamq-operator");
+                self.write_limiter.acquire().await;
+                api.patch(name, &pp, &Patch::Apply(&monitor))
+                    .await
+                    .map(|_| info!(name = %name, "ServiceMonitor reconciled"))
+                    .map_err(Into::into)
+            }
+        };
+        
+        match result {
+            Err(e) if is_not_found(&e) => {
+                info!(name = %name, "Prometheus operator CRDs not installed, skipping ServiceMonitor");
+                Ok(())
+            }
+            other => other,
+        }
+    }
+    
+    /// Apply a VerticalPodAutoscaler (autoscaling.k8s.io/v1) in "Off" mode for
+    /// the broker workload, so it only publishes recommendations. The VPA CRDs
+    /// are optional, so their absence is not an error.
+    async fn reconcile_vpa(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+    ) -> Result<()> {
+        let resource = ApiResource::from_gvk_with_plural(
+            &GroupVersionKind::gvk("autoscaling.k8s.io", "v1", "VerticalPodAutoscaler"),
+            "verticalpodautoscalers",
+        );
+        let api: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), namespace, &resource);
+        let enabled = cluster.spec.vpa.as_ref().is_some_and(|v| v.enabled);
+        
+        if !self.integrations.vertical_pod_autoscaler() {
+            if enabled {
+                info!(name = %name, "VerticalPodAutoscaler CRDs not installed, skipping VPA");
+            }
+            return Ok(());
+        }
+        
+        let result = if enabled {
+            let mut vpa = DynamicObject::new(name, &resource).within(namespace);
+            vpa.metadata.labels = Some(self.common_labels(name));
+            vpa.data = serde_json::json!({
+                "spec": {
+                    "targetRef": {
+                        "apiVersion": "apps/v1",
+                        "kind": cluster.spec.workload_type,
+                        "name": name,
+                    },
+                    "updatePolicy": { "updateMode": "Off" },
+                }
+            });
+            
+            let pp = PatchParams::apply("shazamq-operator");
+            self.write_limiter.acquire().await;
+            api.patch(name, &pp, &Patch::Apply(&vpa))
+                .await
+                .map(|_| info!(name = %name, "VerticalPodAutoscaler reconciled"))
+                .map_err(Into::into)
+        } else {
+            self.delete_managed(&api, name).await
+        };
+        
+        match result {
+            Err(e) if is_not_found(&e) => {
+                info!(name = %name, "VerticalPodAutoscaler CRDs not installed, skipping VPA");
+                Ok(())
+            }
+            other => other,
+        }
+    }
+    
+    async fn reconcile_statefulset(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        group: &BrokerGroup,
+        ports: &Ports,
+    ) -> Result<()> {
+        let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        
+        let replicas = group.replicas;
+        
+        let mut adopting = false;
+        
+        // volumeClaimTemplates are immutable once the StatefulSet exists, so keep the live ones
+        let volume_claim_templates = match api.get_opt(&group.workload_name).await? {
+            Some(existing) => {
+                if existing.labels().get("managed-by").map(String::as_str) != Some("shazamq-operator") {
+                    self.check_adoptable(cluster, &existing, group)?;
+                    adopting = true;
+                }
+                let templates = existing
+                    .spec
+                    .and_then(|s| s.volume_claim_templates)
+                    .unwrap_or_default();
+                // A log dir added after creation would mount a volume that doesn't exist
+                for log_dir in log_dirs(cluster) {
+                    if !templates.iter().any(|t| t.metadata.name.as_deref() == Some(log_dir.name.as_str())) {
+                        bail!(
+                            "spec.storage.logDirs: volume \"{}\" cannot be added to an existing cluster because volumeClaimTemplates are immutable",
+                            log_dir.name
+                        );
+                    }
+                }
+                // Sizes are immutable too. The live ones are kept; update_status reports a shrink as Degraded
+                for change in claim_size_changes(cluster, &templates) {
+                    if change.shrink {
+                        warn!(volume = %change.volume, current = %change.live, requested = %change.requested, "Volume cannot shrink; keeping the live size");
+                    } else {
+                        warn!(volume = %change.volume, current = %change.live, requested = %change.requested, "Volume size increase is not applied to existing PVCs; expand them directly");
+                    }
+                }
+                templates
+            }
+            None => self.volume_claim_templates(cluster, name),
+        };
+        
+        let pod_template = self
+            .pod_template(cluster, name, namespace, group, ports, &volume_claim_templates)
+            .await?;
+        
+        let partition = cluster
+            .spec
+            .update_strategy
+            .as_ref()
+            .and_then(|u| u.rolling_update.as_ref())
+            .and_then(|r| r.partition);
+        let update_strategy = StatefulSetUpdateStrategy {
+            type_: Some("RollingUpdate".to_string()),
+            rolling_update: partition.map(|partition| RollingUpdateStatefulSetStrategy {
+                partition: Some(partition),
+                ..Default::default()
+            }),
+        };
+        
+        let claim_names: Vec<String> = volume_claim_templates.iter().filter_map(|t| t.metadata.name.clone()).collect();
+        
+        let statefulset = StatefulSet {
+            metadata: ObjectMeta {
+                name: Some(group.workload_name.clone()),
+                namespace: Some(namespace.to_string()),
+                labels: Some(self.common_labels(name)),
+                ..Default::default()
+            },
+            spec: Some(StatefulSetSpec {
+                replicas: Some(replicas),
+                selector: LabelSelector {
+                    match_labels: Some(group.selector.clone()),
+                    ..Default::default()
+                },
+                template: pod_template,
+                service_name: headless_service_name(cluster, name),
+                ordinals: group.ordinal_start.map(|start| StatefulSetOrdinals { start: Some(start) }),
+                update_strategy: Some(update_strategy),
+                min_ready_seconds: cluster.spec.min_ready_seconds,
+                persistent_volume_claim_retention_policy: cluster
+                    .spec
+                    .storage
+                    .as_ref()
+                    .and_then(|s| s.pvc_retention_policy.as_ref())
+                    .map(|p| StatefulSetPersistentVolumeClaimRetentionPolicy {
+                        when_deleted: Some(p.when_deleted.clone()),
+                        when_scaled: Some(p.when_scaled.clone()),
+                    }),
+                volume_claim_templates: Some(volume_claim_templates),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        
+        // Adoption takes over fields owned by whatever created the StatefulSet
+        let mut pp = PatchParams::apply("shazamq-operator");
+        if adopting {
+            pp = pp.force();
+        }
+        let patch = Patch::Apply(&statefulset);
+        
+        self.write_limiter.acquire().await;
+        api.patch(&group.workload_name, &pp, &patch).await?;
+        
+        if adopting {
+            self.label_adopted_claims(name, namespace, group, &claim_names).await?;
+            warn!(name = %group.workload_name, "Adopted existing StatefulSet");
+        }
+        
+        info!(name = %group.workload_name, replicas = replicas, "StatefulSet reconciled");
+        
+        Ok(())
+    }
+    
+    /// Run the brokers as a Deployment (stateless tiered mode); all broker volumes are emptyDirs
+    async fn reconcile_deployment(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        group: &BrokerGroup,
+        ports: &Ports,
+    ) -> Result<()> {
+        let api: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+        
+        let replicas = group.replicas;
+        let pod_template = self.pod_template(cluster, name, namespace, group, ports, &[]).await?;
+        
+        let deployment = Deployment {
+            metadata: ObjectMeta {
+                name: Some(group.workload_name.clone()),
+                namespace: Some(namespace.to_string()),
+                labels: Some(self.common_labels(name)),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                replicas: Some(replicas),
+                selector: LabelSelector {
+                    match_labels: Some(group.selector.clone()),
+                    ..Default::default()
+                },
+                template: pod_template,
+                strategy: Some(DeploymentStrategy {
+                    type_: Some("RollingUpdate".to_string()),
+                    ..Default::default()
+                }),
+                min_ready_seconds: cluster.spec.min_ready_seconds,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        
+        let pp = PatchParams::apply("shazamq-operator");
+        let patch = Patch::Apply(&deployment);
+        
+        self.write_limiter.acquire().await;
+        api.patch(&group.workload_name, &pp, &patch).await?;
+        
+        info!(name = %group.workload_name, replicas = replicas, "Deployment reconciled");
+        
+        Ok(())
+    }
+    
+    /// Broker pod template shared by the StatefulSet and Deployment workloads.
+    ///
+    /// Broker volumes without a matching claim in `claims` are backed by an emptyDir.
+    async fn pod_template(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        group: &BrokerGroup,
+        ports: &Ports,
+        claims: &[PersistentVolumeClaim],
+    ) -> Result<PodTemplateSpec> {
+        let image = self.relocate_image(&cluster.spec.image_reference());
+        
+        // Build container
+        let log_filter = cluster.spec.log_filter.clone().unwrap_or_else(|| "info".to_string());
+        let mut env_vars = vec![
+            EnvVar {
+                name: "RUST_LOG".to_string(),
+                value: Some(log_filter),
+                ..Default::default()
+            },
+        ];
+        
+        // Pod identity via the downward API, e.g. for a stable broker id and advertised listeners
+        for (env_name, field_path) in [
+            ("POD_NAME", "metadata.name"),
+            ("POD_NAMESPACE", "metadata.namespace"),
+            ("POD_IP", "status.podIP"),
+        ] {
+            env_vars.push(EnvVar {
+                name: env_name.to_string(),
+                value_from: Some(EnvVarSource {
+                    field_ref: Some(ObjectFieldSelector {
+                        field_path: field_path.to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+        
+        // Per-broker advertised host, expanded by the kubelet from the pod identity above
+        if let Some(template) = &cluster.spec.advertised_host_template {
+            if template.contains("{ordinal}") {
+                env_vars.push(EnvVar {
+                    name: "POD_ORDINAL".to_string(),
+                    value_from: Some(EnvVarSource {
+                        field_ref: Some(ObjectFieldSelector {
+                            field_path: "metadata.labels['apps.kubernetes.io/pod-index']".to_string(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                });
+            }
+            env_vars.push(EnvVar {
+                name: "SHAZAMQ_ADVERTISED_HOST".to_string(),
+                value: Some(template.replace("{pod}", "$(POD_NAME)").replace("{ordinal}", "$(POD_ORDINAL)")),
+                ..Default::default()
+            });
+        }
+        
+        // Add mirror configuration if enabled
+        if let Some(mirror) = &cluster.spec.mirror {
+            if mirror.enabled {
+                env_vars.push(EnvVar {
+                    name: "SHAZAMQ_MIRROR_ENABLED".to_string(),
+                    value: Some("true".to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+        
+        // Allocator tuning, validated for the well-known variables
+        for var in cluster.spec.runtime.iter().flat_map(|r| r.env.iter().flatten()) {
+            env_vars.push(EnvVar {
+                name: var.name.clone(),
+                value: Some(var.value.clone()),
+                ..Default::default()
+            });
+        }
+        
+        // Debug-only: wrap the broker in a profiler and/or add allocator env
+        let debug = cluster.spec.debug.as_ref().filter(|d| d.enabled);
+        if let Some(debug) = debug {
+            warn!(name = %name, "Debug mode enabled; broker runs under the configured profiler settings");
+            for var in debug.env.iter().flatten() {
+                env_vars.push(EnvVar {
+                    name: var.name.clone(),
+                    value: Some(var.value.clone()),
+                    ..Default::default()
+                });
+            }
+        }
+        let command = debug.and_then(|d| {
+            let mut command = d.profiler_args.clone()?;
+            command.extend(d.broker_command.iter().cloned());
+            Some(command)
+        });
+        
+        let mut volume_mounts = vec![
+            VolumeMount {
+                name: "data".to_string(),
+                mount_path: data_path(cluster),
+                ..Default::default()
+            },
+            VolumeMount {
+                name: "config".to_string(),
+                mount_path: "/etc/shazamq".to_string(),
+                ..Default::default()
+            },
+        ];
+        for log_dir in log_dirs(cluster) {
+            volume_mounts.push(VolumeMount {
+                name: log_dir.name.clone(),
+                mount_path: log_dir.path.clone(),
+                ..Default::default()
+            });
+        }
+        if cluster.spec.inter_broker_tls_secret().is_some() {
+            volume_mounts.push(VolumeMount {
+                name: "inter-broker-tls".to_string(),
+                mount_path: INTER_BROKER_TLS_DIR.to_string(),
+                read_only: Some(true),
+                ..Default::default()
+            });
+        }
+        if cluster.spec.trust_bundle().is_some() {
+            volume_mounts.push(VolumeMount {
+                name: "trust-bundle".to_string(),
+                mount_path: TRUST_BUNDLE_DIR.to_string(),
+                read_only: Some(true),
+                ..Default::default()
+            });
+        }
+        
+        let mut container_ports = vec![
+            ContainerPort {
+                name: Some("kafka".to_string()),
+                container_port: ports.kafka,
+                ..Default::default()
+            },
+        ];
+        if ports.metrics_enabled {
+            container_ports.push(ContainerPort {
+                name: Some("metrics".to_string()),
+                container_port: ports.metrics,
+                ..Default::default()
+            });
+        }
+        if let Some(inter_broker) = ports.inter_broker {
+            container_ports.push(ContainerPort {
+                name: Some("inter-broker".to_string()),
+                container_port: inter_broker,
+                ..Default::default()
+            });
+        }
+        
+        let probes = cluster.spec.probes.as_ref();
+        let container = Container {
+            name: "shazamq".to_string(),
+            image: Some(image.clone()),
+            image_pull_policy: Some(cluster.spec.image_pull_policy()),
+            ports: Some(container_ports),
+            env: Some(env_vars),
+            volume_mounts: Some(volume_mounts.clone()),
+            command,
+            liveness_probe: probes.and_then(|p| p.liveness.as_ref()).map(|p| probe(p, None)),
+            readiness_probe: probes.and_then(|p| p.readiness.as_ref()).map(|p| probe(p, None)),
+            startup_probe: probes
+                .and_then(|p| p.startup.as_ref())
+                .map(|p| probe(p, Some(STARTUP_FAILURE_THRESHOLD))),
+            lifecycle: cluster.spec.lifecycle.as_ref().map(|l| Lifecycle {
+                post_start: l.post_start.as_ref().map(lifecycle_handler),
+                pre_stop: l.pre_stop.as_ref().map(lifecycle_handler),
+            }),
+            args: Some(vec![
+                "--config".to_string(),
+                "/etc/shazamq/config.toml".to_string(),
+            ]),
+            ..Default::default()
+        };
+        
+        // User-supplied init containers share the broker's data and config volumes
+        let init_containers: Vec<Container> = cluster
+            .spec
+            .init_containers
+            .iter()
+            .flatten()
+            .map(|init| Container {
+                name: init.name.clone(),
+                image: Some(self.relocate_image(&init.image)),
+                image_pull_policy: init.image_pull_policy.clone(),
+                command: init.command.clone(),
+                args: init.args.clone(),
+                env: init.env.as_ref().map(|env| {
+                    env.iter()
+                        .map(|e| EnvVar {
+                            name: e.name.clone(),
+                            value: Some(e.value.clone()),
+                            ..Default::default()
+                        })
+                        .collect()
+                }),
+                volume_mounts: Some(volume_mounts.clone()),
+                ..Default::default()
+            })
+            .collect();
+        
+        let mut pod_labels = group.selector.clone();
+        if let Some(labels) = &cluster.spec.pod_labels {
+            pod_labels.extend(labels.clone());
+        }
+        
+        let mut pod_annotations = cluster.spec.pod_annotations.clone().unwrap_or_default();
+        // Mirrors `kubectl rollout restart`: the pod template only changes when the timestamp does
+        if let Some(restarted_at) = cluster.annotations().get(RESTARTED_AT_ANNOTATION) {
+            pod_annotations.insert(RESTARTED_AT_ANNOTATION.to_string(), restarted_at.clone());
+        }
+        // Roll the brokers whenever the rendered config or a referenced Secret's data changes
+        let secret_checksums = self.secret_checksums(cluster, namespace).await?;
+        let mut config_hash_input = self.group_config_toml(cluster, group, ports);
+        // Shown by `kubectl describe pod`. The replica count is deliberately left out:
+        // these only change with the config or the enabled features, never on a scale
+        pod_annotations.insert(format!("{}config-bytes", INFO_ANNOTATION_PREFIX), config_hash_input.len().to_string());
+        pod_annotations.insert(format!("{}features", INFO_ANNOTATION_PREFIX), enabled_features(cluster, group).join(","));
+        for (secret_name, checksum) in &secret_checksums {
+            config_hash_input.push_str(&format!("\n# secret {}={}", secret_name, checksum));
+            pod_annotations.insert(secret_checksum_annotation(secret_name), checksum.clone());
+        }
+        let config_hash = sha256_hex(config_hash_input.as_bytes());
+        // Template-only label (never in the selector) telling old and new brokers apart mid-rollout
+        let revision = sha256_hex(format!("{}\n{}", config_hash, image).as_bytes());
+        pod_labels.insert(REVISION_LABEL.to_string(), revision[..REVISION_LABEL_LEN].to_string());
+        pod_annotations.insert(CONFIG_HASH_ANNOTATION.to_string(), config_hash);
+        // Legacy scrape annotations understood by kubernetes_sd_configs pod discovery
+        if cluster.spec.monitoring.as_ref().is_some_and(|m| m.enabled && m.annotations) {
+            pod_annotations.insert("prometheus.io/scrape".to_string(), "true".to_string());
+            pod_annotations.insert("prometheus.io/port".to_string(), ports.metrics.to_string());
+            pod_annotations.insert("prometheus.io/path".to_string(), metrics_path(cluster));
+        }
+        
+        let mut volumes = vec![
+            Volume {
+                name: "config".to_string(),
+                config_map: Some(ConfigMapVolumeSource {
+                    name: Some(group.config_name.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ];
+        if let Some(secret_name) = cluster.spec.inter_broker_tls_secret() {
+            volumes.push(Volume {
+                name: "inter-broker-tls".to_string(),
+                secret: Some(SecretVolumeSource {
+                    secret_name: Some(secret_name),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+        if let Some(bundle) = cluster.spec.trust_bundle() {
+            volumes.push(Volume {
+                name: "trust-bundle".to_string(),
+                config_map: bundle.config_map_name.as_ref().map(|name| ConfigMapVolumeSource {
+                    name: Some(name.clone()),
+                    ..Default::default()
+                }),
+                secret: bundle.secret_name.as_ref().map(|name| SecretVolumeSource {
+                    secret_name: Some(name.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
+        // Volumes without a claim template use storage.existingClaim for data, and otherwise
+        // (ephemeral storage, Deployment workloads) are backed by an emptyDir
+        let existing_claim = cluster.spec.storage.as_ref().and_then(|s| s.existing_claim.as_ref());
+        let broker_volumes = std::iter::once("data").chain(log_dirs(cluster).iter().map(|d| d.name.as_str()));
+        for volume_name in broker_volumes {
+            if claims.iter().any(|t| t.metadata.name.as_deref() == Some(volume_name)) {
+                continue;
+            }
+            match existing_claim.filter(|_| volume_name == "data") {
+                Some(claim_name) => volumes.push(Volume {
+                    name: volume_name.to_string(),
+                    persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
+                        claim_name: claim_name.clone(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                None => volumes.push(Volume {
+                    name: volume_name.to_string(),
+                    empty_dir: Some(EmptyDirVolumeSource::default()),
+                    ..Default::default()
+                }),
+            }
+        }
+        
+        // On-demand troubleshooting shell sharing the broker's volumes and process namespace;
+        // clearing the annotation removes it again with the next rollout
+        let mut containers = vec![container];
+        let debug_requested = cluster.annotations().get(DEBUG_ANNOTATION).map(String::as_str) == Some("true");
+        let debug_sidecar = match (&self.debug_sidecar_image, debug_requested) {
+            (Some(image), true) => Some(Container {
+                name: "debug".to_string(),
+                image: Some(self.relocate_image(image)),
+                command: Some(vec!["sleep".to_string(), "infinity".to_string()]),
+                volume_mounts: Some(volume_mounts.clone()),
+                ..Default::default()
+            }),
+            (None, true) => {
+                warn!(name = %name, "Ignoring {} annotation; debug sidecars are not allowed (ALLOW_DEBUG_SIDECAR)", DEBUG_ANNOTATION);
+                None
+            }
+            (_, false) => None,
+        };
+        let share_process_namespace = debug_sidecar.is_some().then_some(true);
+        containers.extend(debug_sidecar);
+        
+        // Unsafe sysctls are rejected at pod admission unless the kubelet allows them
+        let sysctls = cluster.spec.sysctls.as_ref().filter(|s| !s.is_empty());
+        if let Some(unsafe_sysctl) = sysctls.into_iter().flatten().find(|s| !is_safe_sysctl(&s.name)) {
+            warn!(name = %name, sysctl = %unsafe_sysctl.name, "Unsafe sysctl requested; brokers will not start unless the kubelet allows it");
+        }
+        let security_context = sysctls.map(|sysctls| PodSecurityContext {
+            sysctls: Some(
+                sysctls
+                    .iter()
+                    .map(|s| Sysctl {
+                        name: s.name.clone(),
+                        value: s.value.clone(),
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+        
+        let pod_template = PodTemplateSpec {
+            metadata: Some(ObjectMeta {
+                labels: Some(pod_labels),
+                annotations: Some(pod_annotations),
+                ..Default::default()
+            }),
+            spec: Some(PodSpec {
+                containers,
+                share_process_namespace,
+                security_context,
+                init_containers: (!init_containers.is_empty()).then_some(init_containers),
+                volumes: Some(volumes),
+                node_selector: group.node_selector.clone(),
+                automount_service_account_token: cluster.spec.automount_service_account_token,
+                affinity: self.anti_affinity(cluster, &group.selector),
+                topology_spread_constraints: topology_spread_constraints(cluster, &group.selector),
+                ..Default::default()
+            }),
+        };
+        
+        Ok(pod_template)
+    }
+    
+    /// Broker version running on the pods of the StatefulSet's current revision
+    async fn observed_version(&self, sts: &StatefulSet, name: &str, namespace: &str) -> Result<Option<String>> {
+        let Some(revision) = sts.status.as_ref().and_then(|s| s.current_revision.as_ref()) else {
+            return Ok(None);
+        };
+        
+        let api: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let selector = format!(
+            "{},controller-revision-hash={}",
+            label_selector(&self.selector_labels(name)),
+            revision
+        );
+        let pods = api.list(&ListParams::default().labels(&selector).limit(1)).await?;
+        
+        Ok(pods
+            .items
+            .first()
+            .and_then(|pod| pod.spec.as_ref())
+            .and_then(|spec| spec.containers.iter().find(|c| c.name == "shazamq"))
+            .and_then(|c| c.image.as_deref())
+            .and_then(split_image_tag)
+            .map(|(_, tag)| tag))
+    }
+    
+    /// One entry per primary broker pod, ordered by ordinal, with leadership when known
+    async fn broker_statuses(
+        &self,
+        name: &str,
+        namespace: &str,
+        metadata: Option<&ClusterMetadata>,
+    ) -> Result<Vec<BrokerStatus>> {
+        let api: Api<Pod> = Api::namespaced(self.client.clone(), namespace);
+        let selector = label_selector(&self.selector_labels(name));
+        
+        let mut brokers: Vec<BrokerStatus> = api
+            .list(&ListParams::default().labels(&selector))
+            .await?
+            .into_iter()
+            .filter_map(|pod| {
+                let pod_name = pod.name_any();
+                // StatefulSet pods are named <name>-<ordinal>; the ordinal is the broker id
+                let id: i32 = pod_name.rsplit('-').next()?.parse().ok()?;
+                let ready = pod
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.conditions.as_ref())
+                    .is_some_and(|c| c.iter().any(|c| c.type_ == "Ready" && c.status == "True"));
+                Some(BrokerStatus {
+                    id,
+                    pod: pod_name,
+                    ready,
+                    leader: metadata.is_some_and(|m| m.controller_id == id),
+                    leader_partitions: metadata
+                        .and_then(|m| m.brokers.iter().find(|b| b.id == id))
+                        .map(|b| b.leader_partitions),
+                })
+            })
+            .collect();
+        brokers.sort_by_key(|b| b.id);
+        
+        Ok(brokers)
+    }
+    
+    async fn reconcile_pod_disruption_budget(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+    ) -> Result<()> {
+        let api: Api<PodDisruptionBudget> = Api::namespaced(self.client.clone(), namespace);
+        
+        let Some(config) = cluster.spec.pod_disruption_budget.as_ref().filter(|p| p.enabled) else {
+            return self.delete_managed(&api, name).await;
+        };
+        
+        let pdb = PodDisruptionBudget {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some(namespace.to_string()),
+                labels: Some(self.common_labels(name)),
+                ..Default::default()
+            },
+            spec: Some(PodDisruptionBudgetSpec {
+                max_unavailable: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(
+                    config.max_unavailable,
+                )),
+                selector: Some(LabelSelector {
+                    match_labels: Some(self.selector_labels(name)),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        
+        let pp = PatchParams::apply("shazamq-operator");
+        let patch = Patch::Apply(&pdb);
+        
+        self.write_limiter.acquire().await;
+        api.patch(name, &pp, &patch).await?;
+        
+        info!(name = %name, "PodDisruptionBudget reconciled");
+        
+        Ok(())
+    }
+    
+    /// Reconcile the follower-only read replica group.
+    ///
+    /// Read replicas run in their own StatefulSet with their own ConfigMap and
+    /// client Service, but register through the shared headless Service so the
+    /// primary brokers see them as cluster members.
+    async fn reconcile_read_replicas(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        ports: &Ports,
+    ) -> Result<()> {
+        let Some(mut group) = BrokerGroup::read_replicas(cluster, name) else {
+            let read_name = format!("{}-read", name);
+            self.delete_managed(&Api::<StatefulSet>::namespaced(self.client.clone(), namespace), &read_name)
+                .await?;
+            self.delete_managed(&Api::<Service>::namespaced(self.client.clone(), namespace), &read_name)
+                .await?;
+            self.delete_managed(
+                &Api::<ConfigMap>::namespaced(self.client.clone(), namespace),
+                &format!("{}-read-config", name),
+            )
+            .await?;
+            return Ok(());
+        };
+        
+        self.reconcile_configmap(cluster, name, namespace, &mut group, ports).await?;
+        
+        let api: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+        let port = cluster.spec.service.as_ref().map(|s| s.port).unwrap_or(9092);
+        let service = Service {
+            metadata: ObjectMeta {
+                name: Some(group.workload_name.clone()),
+                namespace: Some(namespace.to_string()),
+                labels: Some(self.common_labels(name)),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                selector: Some(group.selector.clone()),
+                ports: Some(vec![
+                    ServicePort {
+                        name: Some("kafka".to_string()),
+                        port,
+                        target_port: Some(k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(ports.kafka)),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        
+        let pp = PatchParams::apply("shazamq-operator");
+        self.write_limiter.acquire().await;
+        api.patch(&group.workload_name, &pp, &Patch::Apply(&service)).await?;
+        
+        info!(name = %group.workload_name, "Read replica service reconciled");
+        
+        self.reconcile_statefulset(cluster, name, namespace, &group, ports).await
+    }
+    
+    /// Record the workload state in status; returns whether the cluster is fully Running
+    async fn update_status(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        ports: &Ports,
+        replicas: i32,
+    ) -> Result<bool> {
+        // Get current broker workload
+        let sts_api: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        let stateful = cluster.spec.workload_type != "Deployment";
+        let target_version = cluster.spec.version.clone();
+        let regions = BrokerGroup::regions(cluster, name, &self.selector_labels(name));
+        let mut region_statuses = None;
+        let mut claim_changes = Vec::new();
+        let (ready_replicas, observed_version) = if !regions.is_empty() {
+            let mut ready = 0;
+            let mut versions = Vec::new();
+            let mut statuses = Vec::new();
+            for (spec, region) in cluster.spec.regions.iter().flatten().zip(&regions) {
+                let sts = sts_api.get(&region.workload_name).await?;
+                claim_changes.extend(live_claim_size_changes(cluster, &sts));
+                let region_ready = sts.status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0);
+                ready += region_ready;
+                versions.extend(self.observed_version(&sts, name, namespace).await?);
+                statuses.push(RegionStatus {
+                    name: spec.name.clone(),
+                    replicas: region.replicas,
+                    ready_replicas: region_ready,
+                });
+            }
+            region_statuses = Some(statuses);
+            // Regions roll independently, so report a lagging region's version until they all match
+            let observed = versions.iter().find(|v| **v != target_version).or(versions.first()).cloned();
+            (ready, observed)
+        } else if stateful {
+            let sts = sts_api.get(name).await?;
+            claim_changes.extend(live_claim_size_changes(cluster, &sts));
+            let ready = sts.status.as_ref().and_then(|s| s.ready_replicas).unwrap_or(0);
+            (ready, self.observed_version(&sts, name, namespace).await?)
+        } else {
+            let deployments: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+            let deployment = deployments.get(name).await?;
+            let deployment_status = deployment.status.unwrap_or_default();
+            // A Deployment has no current revision to inspect, so the new
+            // version counts as observed once the rollout has completed
+            let rolled_out = deployment_status.updated_replicas == Some(replicas)
+                && deployment_status.observed_generation >= deployment.metadata.generation;
+            let observed = if rolled_out {
+                Some(target_version.clone())
+            } else {
+                cluster.status.as_ref().and_then(|s| s.observed_version.clone())
+            };
+            (deployment_status.ready_replicas.unwrap_or(0), observed)
+        };
+        
+        let metrics_port = cluster.spec.service.as_ref().map(|s| s.metrics_port).unwrap_or(9090);
+        let metrics_service = metrics_service_name(cluster, name);
+        
+        let raw_phase = if ready_replicas == replicas {
+            // Pod readiness doesn't imply the brokers have formed a quorum. An
+            // unreachable admin endpoint must not hold the phase back, so only
+            // an explicit "not established" answer reports Stabilizing.
+            let quorum = if cluster.spec.wait_for_quorum {
+                self.admin.quorum_status(&metrics_service, namespace, metrics_port).await
+            } else {
+                None
+            };
+            match quorum {
+                Some(q) if !q.established => "Stabilizing",
+                _ => "Running",
+            }
+        } else if ready_replicas > 0 {
+            "Updating"
+        } else {
             "Creating"
         };
         
-        let status = ShazamqClusterStatus {
-            phase: Some(phase.to_string()),
-            replicas: Some(replicas),
-            ready_replicas: Some(ready_replicas),
-            conditions: None,
-            brokers: None,
+        let mut conditions = cluster
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.clone())
+            .unwrap_or_default();
+        upsert_condition(&mut conditions, "ReconcileError", "False", "ReconcileSucceeded", "");
+        match &observed_version {
+            Some(observed) if *observed != target_version => upsert_condition(
+                &mut conditions,
+                "UpgradeInProgress",
+                "True",
+                "VersionMismatch",
+                &format!("Rolling out {} (running {})", target_version, observed),
+            ),
+            Some(_) => upsert_condition(&mut conditions, "UpgradeInProgress", "False", "VersionConverged", ""),
+            None => {}
+        }
+        if ready_replicas < replicas {
+            upsert_condition(
+                &mut conditions,
+                "ReplicasReady",
+                "False",
+                "ReplicasUnavailable",
+                &format!("{} of {} brokers ready", ready_replicas, replicas),
+            );
+        } else {
+            upsert_condition(&mut conditions, "ReplicasReady", "True", "AllReplicasReady", "");
+        }
+        
+        // Hysteresis: a steady cluster keeps its phase through a brief readiness dip
+        // (e.g. one broker restarting) until the dip outlasts phaseDebounceSeconds
+        let below_target_since = conditions
+            .iter()
+            .find(|c| c.r#type == "ReplicasReady" && c.status == "False")
+            .and_then(|c| chrono::DateTime::parse_from_rfc3339(&c.last_transition_time).ok());
+        let within_debounce = below_target_since.is_some_and(|since| {
+            chrono::Utc::now().signed_duration_since(since)
+                < chrono::Duration::seconds(cluster.spec.phase_debounce_seconds.into())
+        });
+        let previous_phase = cluster.status.as_ref().and_then(|s| s.phase.as_deref());
+        let phase = match previous_phase {
+            Some(steady @ ("Running" | "Stabilizing")) if raw_phase == "Updating" && within_debounce => steady,
+            _ => raw_phase,
+        };
+        
+        let read_replicas_ready = if BrokerGroup::read_replicas(cluster, name).is_some() {
+            sts_api
+                .get_opt(&format!("{}-read", name))
+                .await?
+                .and_then(|s| s.status)
+                .and_then(|s| s.ready_replicas)
+                .or(Some(0))
+        } else {
+            None
+        };
+        
+        // Correlate config changes with restarts: the timestamp only moves when the rendered config does
+        let reconciled_at = chrono::Utc::now();
+        let now = reconciled_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let brokers = BrokerGroup::brokers(cluster, name, self.selector_labels(name));
+        let rendered_config_hash = sha256_hex(self.group_config_toml(cluster, &brokers, ports).as_bytes());
+        let previous = cluster.status.as_ref();
+        let last_config_change_time = match previous.and_then(|s| s.rendered_config_hash.as_ref()) {
+            Some(hash) if *hash == rendered_config_hash => previous.and_then(|s| s.last_config_change_time.clone()),
+            _ => Some(now),
+        };
+        
+        // Degraded while spec.autoRollback keeps a crash-looping config reverted
+        let mut problems = Vec::new();
+        let rolled_back = if cluster.spec.auto_rollback().is_some() {
+            let configmaps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+            configmaps
+                .get_opt(&brokers.config_name)
+                .await?
+                .and_then(|c| c.metadata.annotations)
+                .and_then(|a| a.get(ROLLED_BACK_CONFIG_ANNOTATION).cloned())
+                == Some(rendered_config_hash.clone())
+        } else {
+            false
+        };
+        if rolled_back {
+            problems.push((
+                "ConfigRolledBack",
+                "Brokers crash-looped after a config change; serving the last-known-good config until the spec changes"
+                    .to_string(),
+            ));
+        }
+        // Regions share the spec, so a shrink shows up once per region; report each volume once
+        let mut shrunk_volumes = Vec::new();
+        for change in claim_changes.iter().filter(|c| c.shrink) {
+            if !shrunk_volumes.contains(&change.volume) {
+                shrunk_volumes.push(change.volume.clone());
+                problems.push((
+                    "StorageShrinkRefused",
+                    format!(
+                        "volume {} cannot shrink from {} to {}; PVCs can't be made smaller, so the live size is kept",
+                        change.volume, change.live, change.requested
+                    ),
+                ));
+            }
+        }
+        if let Some(min) = cluster.spec.min_replicas.filter(|min| cluster.spec.replicas < *min) {
+            problems.push((
+                "ScaleDownRefused",
+                format!(
+                    "spec.replicas {} is below spec.minReplicas {}; keeping {} brokers until minReplicas is lowered",
+                    cluster.spec.replicas, min, replicas
+                ),
+            ));
+        }
+        set_degraded(&mut conditions, &problems);
+        
+        let mut status = ShazamqClusterStatus {
+            phase: Some(phase.to_string()),
+            read_replicas_ready,
+            replicas: Some(replicas),
+            ready_replicas: Some(ready_replicas),
+            target_version: Some(target_version.clone()),
+            observed_version,
+            endpoints: Some(self.client_endpoints(cluster, name, namespace)),
+            // Status is the last reconcile step, so this marks a successful pass
+            last_reconcile_time: Some(last_reconcile_time(
+                previous.and_then(|s| s.last_reconcile_time.as_deref()),
+                reconciled_at,
+            )),
+            rendered_config_hash: Some(rendered_config_hash),
+            last_config_change_time,
+            conditions: Some(conditions),
+            regions: region_statuses,
+            ..Default::default()
+        };
+        
+        // Cluster stats and leadership are best-effort: skipped when the metrics listener is off or no broker is ready
+        let mut metadata = None;
+        if cluster.spec.metrics_enabled() && ready_replicas > 0 {
+            if let Some(stats) = self.admin.cluster_stats(&metrics_service, namespace, metrics_port).await {
+                status.topic_count = Some(stats.topics);
+                status.partition_count = Some(stats.partitions);
+                status.under_replicated_partitions = Some(stats.under_replicated_partitions);
+            }
+            metadata = self.admin.cluster_metadata(&metrics_service, namespace, metrics_port).await;
+        }
+        // Deployment pods have no ordinal, so there is no stable broker id to report
+        if stateful {
+            status.brokers = Some(self.broker_statuses(name, namespace, metadata.as_ref()).await?);
+        }
+        if stateful && cluster.spec.rebalance_on_scale {
+            self.track_rebalance(cluster, namespace, &metrics_service, metrics_port, ready_replicas, &mut status)
+                .await;
+        }
+        
+        self.patch_status(cluster, namespace, status).await?;
+        
+        info!(name = %name, phase = phase, ready = ready_replicas, "Status updated");
+        
+        // The undebounced phase, so a rollout hidden behind a steady phase still counts as converging
+        Ok(raw_phase == "Running")
+    }
+    
+    /// Drive rebalanceOnScale through the `Rebalancing` condition. Once every broker
+    /// of a scale-up is ready, a reassignment is started; while the condition is
+    /// True nothing new is started, and once the brokers report it finished,
+    /// status.rebalancedReplicas records the broker count it balanced across.
+    /// Admin calls are best-effort: an unreachable endpoint is retried next pass.
+    async fn track_rebalance(
+        &self,
+        cluster: &ShazamqCluster,
+        namespace: &str,
+        metrics_service: &str,
+        metrics_port: i32,
+        ready_replicas: i32,
+        status: &mut ShazamqClusterStatus,
+    ) {
+        // The applied broker count, which stays above spec.replicas while minReplicas refuses a scale-down
+        let replicas = status.replicas.unwrap_or(cluster.spec.replicas);
+        let previous = cluster.status.as_ref();
+        let conditions = status.conditions.get_or_insert_with(Vec::new);
+        let in_flight = conditions.iter().any(|c| c.r#type == "Rebalancing" && c.status == "True");
+        
+        if in_flight {
+            let Some(state) = self.admin.rebalance_status(metrics_service, namespace, metrics_port).await else {
+                return;
+            };
+            if !state.in_progress {
+                upsert_condition(
+                    conditions,
+                    "Rebalancing",
+                    "False",
+                    "Completed",
+                    &format!("Partitions balanced across {} brokers", replicas),
+                );
+                status.rebalanced_replicas = Some(replicas);
+                info!(replicas = replicas, "Partition rebalance finished");
+            }
+            return;
+        }
+        
+        // The first pass only records a baseline, so enabling the option or creating a cluster starts nothing
+        let Some(rebalanced) = previous.and_then(|s| s.rebalanced_replicas) else {
+            status.rebalanced_replicas = Some(replicas);
+            return;
+        };
+        if replicas < rebalanced {
+            // Only a scale-up leaves brokers empty, so a scale-down just moves the baseline
+            status.rebalanced_replicas = Some(replicas);
+            return;
+        }
+        if replicas == rebalanced || ready_replicas < replicas {
+            return;
+        }
+        
+        if self.admin.start_rebalance(metrics_service, namespace, metrics_port).await.is_some() {
+            upsert_condition(
+                conditions,
+                "Rebalancing",
+                "True",
+                "Started",
+                &format!("Reassigning partitions from {} to {} brokers", rebalanced, replicas),
+            );
+            info!(from = rebalanced, to = replicas, "Started partition rebalance after scale-up");
+        } else {
+            warn!(replicas = replicas, "Could not start partition rebalance; retrying next reconcile");
+        }
+    }
+    
+    /// Upsert a single condition without touching other status fields
+    async fn set_condition(
+        &self,
+        cluster: &ShazamqCluster,
+        namespace: &str,
+        type_: &str,
+        status: &str,
+        reason: &str,
+        message: &str,
+    ) -> Result<()> {
+        let mut conditions = cluster
+            .status
+            .as_ref()
+            .and_then(|s| s.conditions.clone())
+            .unwrap_or_default();
+        upsert_condition(&mut conditions, type_, status, reason, message);
+        
+        let status = ShazamqClusterStatus {
+            conditions: Some(conditions),
+            ..Default::default()
+        };
+        self.patch_status(cluster, namespace, status).await
+    }
+    
+    /// Merge-patch the status fields that are set in `status`.
+    ///
+    /// Fields left as `None` are omitted from the patch and keep their current
+    /// value, so independent status writers never clobber each other. The
+    /// conditions list is written whole, so the patch is guarded by the
+    /// resourceVersion it was computed from; on a conflict the condition
+    /// changes are replayed onto the latest object and the patch is retried.
+    async fn patch_status(
+        &self,
+        cluster: &ShazamqCluster,
+        namespace: &str,
+        status: ShazamqClusterStatus,
+    ) -> Result<()> {
+        let api: Api<ShazamqCluster> = Api::namespaced(self.client.clone(), namespace);
+        let name = cluster.name_any();
+        let limiter = &self.write_limiter;
+        
+        let write = |patch: serde_json::Value| {
+            let (api, name) = (api.clone(), name.clone());
+            async move {
+                limiter.acquire().await;
+                api.patch_status(&name, &PatchParams::default(), &Patch::Merge(patch)).await.map(|_| ())
+            }
+        };
+        let latest = || {
+            let (api, name) = (api.clone(), name.clone());
+            async move { api.get_status(&name).await }
+        };
+        patch_status_with_retry(cluster, &format!("{}/{}", namespace, name), status, write, latest).await
+    }
+    
+    /// Delete an optional child object that is no longer wanted, but only if
+    /// it carries the operator's managed-by label
+    async fn delete_managed<K>(&self, api: &Api<K>, object_name: &str) -> Result<()>
+    where
+        K: Resource + Clone + DeserializeOwned + Debug,
+    {
+        let Some(existing) = api.get_opt(object_name).await? else {
+            return Ok(());
+        };
+        if existing.labels().get("managed-by").map(String::as_str) != Some("shazamq-operator") {
+            return Ok(());
+        }
+        
+        self.write_limiter.acquire().await;
+        api.delete(object_name, &DeleteParams::default()).await?;
+        
+        info!(name = %object_name, "Pruned disabled resource");
+        
+        Ok(())
+    }
+    
+    /// Replica count for the next step of an incremental scale-up. The live count
+    /// is raised by one only once the StatefulSet has observed its spec, every
+    /// existing broker is ready and, when the admin API answers, every broker id
+    /// is in the cluster metadata. Scale-downs and steady state pass straight through.
+    async fn scale_up_step(
+        &self,
+        cluster: &ShazamqCluster,
+        name: &str,
+        namespace: &str,
+        api: &Api<StatefulSet>,
+    ) -> Result<i32> {
+        let desired = cluster.spec.replicas;
+        let Some(existing) = api.get_opt(name).await? else {
+            return Ok(desired.min(1));
+        };
+        let current = existing.spec.as_ref().and_then(|s| s.replicas).unwrap_or(1);
+        if desired <= current {
+            return Ok(desired);
+        }
+        
+        let status = existing.status.unwrap_or_default();
+        let settled = status.observed_generation >= existing.metadata.generation
+            && status.ready_replicas.unwrap_or(0) >= current;
+        if !settled {
+            info!(name = %name, current = current, desired = desired, "Waiting for brokers to become ready before scaling further");
+            return Ok(current);
+        }
+        
+        if cluster.spec.metrics_enabled() {
+            let metrics_port = cluster.spec.service.as_ref().map(|s| s.metrics_port).unwrap_or(9090);
+            let metrics_service = metrics_service_name(cluster, name);
+            if let Some(metadata) = self.admin.cluster_metadata(&metrics_service, namespace, metrics_port).await {
+                if let Some(missing) = (0..current).find(|id| !metadata.brokers.iter().any(|b| b.id == *id)) {
+                    info!(name = %name, broker = missing, "Waiting for broker to join before scaling further");
+                    return Ok(current);
+                }
+            }
+        }
+        
+        info!(name = %name, replicas = current + 1, desired = desired, "Adding the next broker");
+        Ok(current + 1)
+    }
+    
+    /// Delete the StatefulSets of regions dropped from spec.regions. Setting or clearing
+    /// spec.regions on a running cluster would move every broker to another StatefulSet,
+    /// so that is refused instead.
+    async fn prune_regions(&self, name: &str, api: &Api<StatefulSet>, regions: &[BrokerGroup]) -> Result<()> {
+        let selector = label_selector(&self.common_labels(name));
+        let regional: Vec<String> = api
+            .list(&ListParams::default().labels(&selector))
+            .await?
+            .into_iter()
+            .filter(|sts| {
+                sts.spec
+                    .as_ref()
+                    .and_then(|s| s.selector.match_labels.as_ref())
+                    .is_some_and(|l| l.contains_key(REGION_LABEL))
+            })
+            .map(|sts| sts.name_any())
+            .collect();
+        
+        if regions.is_empty() {
+            if let Some(existing) = regional.first() {
+                bail!(
+                    "spec.regions: cannot be removed from a running cluster; StatefulSet \"{}\" still holds regional brokers",
+                    existing
+                );
+            }
+            return Ok(());
+        }
+        let primary = api.get_opt(name).await?;
+        if primary.is_some_and(|p| p.labels().get("managed-by").map(String::as_str) == Some("shazamq-operator")) {
+            bail!(
+                "spec.regions: cannot be added to a running cluster; StatefulSet \"{}\" already holds its brokers",
+                name
+            );
+        }
+        
+        for workload_name in regional {
+            if !regions.iter().any(|r| r.workload_name == workload_name) {
+                info!(name = %workload_name, "Removing StatefulSet of a dropped region");
+                self.delete_managed(api, &workload_name).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Refuse to touch a StatefulSet the operator didn't create unless the cluster
+    /// opts in with the adopt annotation and the immutable selector already matches
+    fn check_adoptable(&self, cluster: &ShazamqCluster, existing: &StatefulSet, group: &BrokerGroup) -> Result<()> {
+        if cluster.annotations().get(ADOPT_ANNOTATION).map(String::as_str) != Some("true") {
+            bail!(
+                "StatefulSet \"{}\" already exists and is not managed by the operator; set the {}: \"true\" annotation to adopt it",
+                group.workload_name,
+                ADOPT_ANNOTATION
+            );
+        }
+        let selector = existing.spec.as_ref().and_then(|s| s.selector.match_labels.clone()).unwrap_or_default();
+        if selector != group.selector {
+            bail!(
+                "StatefulSet \"{}\" cannot be adopted: its selector {} must be {} and selectors are immutable",
+                group.workload_name,
+                label_selector(&selector),
+                label_selector(&group.selector)
+            );
+        }
+        Ok(())
+    }
+    
+    /// Label the PVCs of an adopted StatefulSet so pruning and cleanup treat them as managed
+    async fn label_adopted_claims(&self, name: &str, namespace: &str, group: &BrokerGroup, claim_names: &[String]) -> Result<()> {
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), namespace);
+        let patch = serde_json::json!({ "metadata": { "labels": self.common_labels(name) } });
+        for claim_name in claim_names {
+            let start = group.ordinal_start.unwrap_or(0);
+            for ordinal in start..start + group.replicas {
+                let pvc_name = format!("{}-{}-{}", claim_name, group.workload_name, ordinal);
+                if api.get_opt(&pvc_name).await?.is_none() {
+                    continue;
+                }
+                self.write_limiter.acquire().await;
+                api.patch(&pvc_name, &PatchParams::default(), &Patch::Merge(&patch)).await?;
+                info!(name = %pvc_name, "Labeled adopted PVC");
+            }
+        }
+        Ok(())
+    }
+    
+    /// Register the cleanup finalizer, guarded by resourceVersion so other finalizers are kept
+    async fn add_finalizer(&self, cluster: &ShazamqCluster, namespace: &str) -> Result<()> {
+        if cluster.finalizers().iter().any(|f| f == CLEANUP_FINALIZER) {
+            return Ok(());
+        }
+        let mut finalizers = cluster.finalizers().to_vec();
+        finalizers.push(CLEANUP_FINALIZER.to_string());
+        self.patch_finalizers(cluster, namespace, finalizers).await
+    }
+    
+    async fn patch_finalizers(&self, cluster: &ShazamqCluster, namespace: &str, finalizers: Vec<String>) -> Result<()> {
+        let api: Api<ShazamqCluster> = Api::namespaced(self.client.clone(), namespace);
+        let patch = serde_json::json!({
+            "metadata": {
+                "finalizers": finalizers,
+                "resourceVersion": cluster.resource_version(),
+            }
+        });
+        self.write_limiter.acquire().await;
+        api.patch(&cluster.name_any(), &PatchParams::default(), &Patch::Merge(patch))
+            .await
+            .with_context(|| format!("updating finalizers of {}/{}", namespace, cluster.name_any()))?;
+        Ok(())
+    }
+    
+    /// Tear a deleted cluster down in order: the client-facing Services first so
+    /// no new connections arrive, then the brokers, then their PVCs when
+    /// pvcRetentionPolicy.whenDeleted is Delete, and finally everything else.
+    /// Requeues until the brokers are gone, then releases the finalizer.
+    async fn cleanup(&self, cluster: &ShazamqCluster, name: &str, namespace: &str) -> Result<Action> {
+        // A deleted cluster is never reconciled again, so its staleness gauge would only grow
+        self.metrics.forget_cluster(namespace, name);
+        if !cluster.finalizers().iter().any(|f| f == CLEANUP_FINALIZER) {
+            return Ok(Action::await_change());
+        }
+        let read_name = format!("{}-read", name);
+        let admin_name = format!("{}-admin", name);
+        
+        info!(name = %name, "Removing client Services");
+        let services: Api<Service> = Api::namespaced(self.client.clone(), namespace);
+        for service_name in [name.to_string(), format!("{}-metrics", name), read_name.clone()] {
+            self.delete_managed(&services, &service_name).await?;
+        }
+        self.delete_managed(&Api::<Ingress>::namespaced(self.client.clone(), namespace), &admin_name)
+            .await?;
+        if self.integrations.http_route() {
+            let resource = ApiResource::from_gvk_with_plural(
+                &GroupVersionKind::gvk("gateway.networking.k8s.io", "v1", "HTTPRoute"),
+                "httproutes",
+            );
+            let routes: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), namespace, &resource);
+            self.delete_managed(&routes, &admin_name).await?;
+        }
+        
+        // Foreground deletion keeps each workload around until its pods are gone
+        info!(name = %name, "Stopping brokers");
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client.clone(), namespace);
+        let deployments: Api<Deployment> = Api::namespaced(self.client.clone(), namespace);
+        let mut terminating = false;
+        let regions = BrokerGroup::regions(cluster, name, &self.selector_labels(name));
+        for statefulset_name in [name, read_name.as_str()]
+            .into_iter()
+            .chain(regions.iter().map(|r| r.workload_name.as_str()))
+        {
+            terminating |= self.delete_foreground(&statefulsets, statefulset_name).await?;
+        }
+        terminating |= self.delete_foreground(&deployments, name).await?;
+        if terminating {
+            info!(name = %name, "Waiting for brokers to terminate");
+            return Ok(Action::requeue(CLEANUP_REQUEUE));
+        }
+        
+        let delete_pvcs = cluster
+            .spec
+            .storage
+            .as_ref()
+            .and_then(|s| s.pvc_retention_policy.as_ref())
+            .is_some_and(|p| p.when_deleted == "Delete");
+        if delete_pvcs {
+            info!(name = %name, "Deleting broker PVCs");
+            let pvcs: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), namespace);
+            let selector = label_selector(&self.common_labels(name));
+            for pvc in pvcs.list(&ListParams::default().labels(&selector)).await? {
+                self.write_limiter.acquire().await;
+                pvcs.delete(&pvc.name_any(), &DeleteParams::default()).await?;
+                info!(name = %pvc.name_any(), "Deleted PVC");
+            }
+        }
+        
+        info!(name = %name, "Removing remaining resources");
+        self.delete_managed(&services, &headless_service_name(cluster, name)).await?;
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+        for config_name in [
+            format!("{}-config", name),
+            format!("{}-read-config", name),
+            format!("{}-dashboard", name),
+        ] {
+            self.delete_managed(&config_maps, &config_name).await?;
+        }
+        self.delete_managed(&Api::<PodDisruptionBudget>::namespaced(self.client.clone(), namespace), name)
+            .await?;
+        for (enabled, group, kind, plural) in [
+            (self.integrations.service_monitor(), "monitoring.coreos.com", "ServiceMonitor", "servicemonitors"),
+            (self.integrations.vertical_pod_autoscaler(), "autoscaling.k8s.io", "VerticalPodAutoscaler", "verticalpodautoscalers"),
+        ] {
+            if enabled {
+                let resource = ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk(group, "v1", kind), plural);
+                let api: Api<DynamicObject> = Api::namespaced_with(self.client.clone(), namespace, &resource);
+                self.delete_managed(&api, name).await?;
+            }
+        }
+        
+        let finalizers = cluster
+            .finalizers()
+            .iter()
+            .filter(|f| *f != CLEANUP_FINALIZER)
+            .cloned()
+            .collect();
+        self.patch_finalizers(cluster, namespace, finalizers).await?;
+        info!(name = %name, "Cleanup finished");
+        
+        Ok(Action::await_change())
+    }
+    
+    /// Start a foreground delete of a managed workload; returns whether it still exists
+    async fn delete_foreground<K>(&self, api: &Api<K>, object_name: &str) -> Result<bool>
+    where
+        K: Resource + Clone + DeserializeOwned + Debug,
+    {
+        let Some(existing) = api.get_opt(object_name).await? else {
+            return Ok(false);
+        };
+        if existing.labels().get("managed-by").map(String::as_str) != Some("shazamq-operator") {
+            return Ok(false);
+        }
+        if existing.meta().deletion_timestamp.is_none() {
+            self.write_limiter.acquire().await;
+            api.delete(object_name, &DeleteParams::foreground()).await?;
+            info!(name = %object_name, "Deleting workload");
+        }
+        Ok(true)
+    }
+    
+    /// Fail early, with the field name, when spec.security.trustBundle points at a
+    /// missing ConfigMap/Secret or key; otherwise the pods would never start
+    async fn check_trust_bundle(&self, cluster: &ShazamqCluster, namespace: &str) -> Result<()> {
+        let Some(bundle) = cluster.spec.trust_bundle() else {
+            return Ok(());
+        };
+        
+        let (kind, source, has_key) = if let Some(name) = &bundle.config_map_name {
+            let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), namespace);
+            let has_key = api.get_opt(name).await?.map(|cm| {
+                cm.data.is_some_and(|d| d.contains_key(&bundle.key))
+                    || cm.binary_data.is_some_and(|d| d.contains_key(&bundle.key))
+            });
+            ("ConfigMap", name, has_key)
+        } else if let Some(name) = &bundle.secret_name {
+            let api: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
+            let has_key = api
+                .get_opt(name)
+                .await?
+                .map(|secret| secret.data.is_some_and(|d| d.contains_key(&bundle.key)));
+            ("Secret", name, has_key)
+        } else {
+            return Ok(());
         };
         
-        let mut cluster_clone = cluster.clone();
-        cluster_clone.status = Some(status);
-        
-        let pp = PatchParams::apply("shazamq-operator");
-        let patch = Patch::Apply(&cluster_clone);
-        
-        api.patch_status(name, &pp, &patch).await?;
+        match has_key {
+            None => bail!("spec.security.trustBundle: {} \"{}\" not found in namespace {}", kind, source, namespace),
+            Some(false) => bail!("spec.security.trustBundle: {} \"{}\" has no key \"{}\"", kind, source, bundle.key),
+            Some(true) => Ok(()),
+        }
+    }
+    
+    /// Checksum of the data of every referenced Secret, keyed by Secret name.
+    /// A Secret that doesn't exist yet is recorded as "missing", so creating it
+    /// later still changes the checksum.
+    async fn secret_checksums(&self, cluster: &ShazamqCluster, namespace: &str) -> Result<BTreeMap<String, String>> {
+        let secrets: Api<Secret> = Api::namespaced(self.client.clone(), namespace);
         
-        info!(name = %name, phase = phase, ready = ready_replicas, "Status updated");
+        let mut checksums = BTreeMap::new();
+        for secret_name in cluster.spec.referenced_secrets() {
+            let checksum = match secrets.get_opt(&secret_name).await? {
+                Some(secret) => {
+                    let mut input = Vec::new();
+                    for (key, value) in secret.data.iter().flatten() {
+                        input.extend_from_slice(key.as_bytes());
+                        input.push(0);
+                        input.extend_from_slice(&value.0);
+                        input.push(0);
+                    }
+                    sha256_hex(&input)
+                }
+                None => "missing".to_string(),
+            };
+            checksums.insert(secret_name, checksum);
+        }
         
-        Ok(())
+        Ok(checksums)
+    }
+    
+    /// Swap the registry host of an image for IMAGE_REGISTRY_OVERRIDE, keeping repository path and tag
+    fn relocate_image(&self, image: &str) -> String {
+        match &self.registry_override {
+            Some(registry) => relocate_image(image, registry),
+            None => image.to_string(),
+        }
+    }
+    
+    /// Render a broker group's config: the cluster config plus any role-specific flags
+    fn group_config_toml(&self, cluster: &ShazamqCluster, group: &BrokerGroup, ports: &Ports) -> String {
+        match &group.served_config {
+            Some(config) => config.clone(),
+            None => broker_config(&cluster.spec, ports, group.follower_only).to_toml(),
+        }
     }
+
     
-    fn generate_config_toml(&self, cluster: &ShazamqCluster) -> String {
-        let mut config = String::new();
+    fn anti_affinity(&self, cluster: &ShazamqCluster, selector: &BTreeMap<String, String>) -> Option<Affinity> {
+        let config = cluster.spec.anti_affinity.as_ref().filter(|a| a.enabled)?;
         
-        config.push_str("[broker]\n");
-        config.push_str("host = \"0.0.0.0\"\n");
-        config.push_str("port = 9092\n");
-        config.push_str("data_dir = \"/data/shazamq\"\n\n");
+        let term = PodAffinityTerm {
+            label_selector: Some(LabelSelector {
+                match_labels: Some(selector.clone()),
+                ..Default::default()
+            }),
+            topology_key: config.topology_key.clone(),
+            ..Default::default()
+        };
         
-        config.push_str("[storage]\n");
-        if let Some(storage) = &cluster.spec.storage {
-            if let Some(segment_bytes) = storage.segment_bytes {
-                config.push_str(&format!("segment_bytes = {}\n", segment_bytes));
+        let anti_affinity = if config.required {
+            PodAntiAffinity {
+                required_during_scheduling_ignored_during_execution: Some(vec![term]),
+                ..Default::default()
             }
-            if let Some(retention_hours) = storage.retention_hours {
-                config.push_str(&format!("retention_hours = {}\n", retention_hours));
+        } else {
+            PodAntiAffinity {
+                preferred_during_scheduling_ignored_during_execution: Some(vec![
+                    WeightedPodAffinityTerm {
+                        weight: 100,
+                        pod_affinity_term: term,
+                    },
+                ]),
+                ..Default::default()
             }
+        };
+        
+        Some(Affinity {
+            pod_anti_affinity: Some(anti_affinity),
+            ..Default::default()
+        })
+    }
+    
+    fn volume_claim_templates(&self, cluster: &ShazamqCluster, name: &str) -> Vec<PersistentVolumeClaim> {
+        let mut labels = self.common_labels(name);
+        if let Some(pvc_labels) = cluster.spec.storage.as_ref().and_then(|s| s.pvc_labels.as_ref()) {
+            labels.extend(pvc_labels.clone());
         }
-        config.push('\n');
         
-        config.push_str("[metrics]\n");
-        config.push_str("enabled = true\n");
-        config.push_str("host = \"0.0.0.0\"\n");
-        config.push_str("port = 9090\n\n");
+        let claim = |claim_name: &str, size: &str, storage_class: Option<String>| PersistentVolumeClaim {
+            metadata: ObjectMeta {
+                name: Some(claim_name.to_string()),
+                labels: Some(labels.clone()),
+                ..Default::default()
+            },
+            spec: Some(PersistentVolumeClaimSpec {
+                access_modes: Some(vec!["ReadWriteOnce".to_string()]),
+                resources: Some(K8sResourceRequirements {
+                    requests: Some({
+                        let mut map = BTreeMap::new();
+                        map.insert(
+                            "storage".to_string(),
+                            k8s_openapi::apimachinery::pkg::api::resource::Quantity(size.to_string()),
+                        );
+                        map
+                    }),
+                    ..Default::default()
+                }),
+                storage_class_name: storage_class,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
         
-        if let Some(tiered) = &cluster.spec.tiered_storage {
-            if tiered.enabled {
-                config.push_str("[tiered_storage]\n");
-                config.push_str("enabled = true\n");
-                config.push_str(&format!("provider = \"{}\"\n", tiered.provider));
-                
-                if let Some(s3) = &tiered.s3 {
-                    config.push_str("\n[tiered_storage.s3]\n");
-                    config.push_str(&format!("bucket = \"{}\"\n", s3.bucket));
-                    config.push_str(&format!("region = \"{}\"\n", s3.region));
-                    config.push_str(&format!("prefix = \"{}\"\n", s3.prefix));
-                }
-                config.push('\n');
-            }
+        let storage = cluster.spec.storage.as_ref();
+        let ephemeral = storage.and_then(|s| s.ephemeral).unwrap_or(false);
+        let existing_claim = storage.is_some_and(|s| s.existing_claim.is_some());
+        let mut templates = Vec::new();
+        if !ephemeral && !existing_claim {
+            let size = storage.and_then(|s| s.size.as_deref()).unwrap_or(DEFAULT_DATA_SIZE);
+            templates.push(claim("data", size, None));
+        }
+        for log_dir in log_dirs(cluster) {
+            templates.push(claim(&log_dir.name, &log_dir.size, log_dir.storage_class_name.clone()));
+        }
+        templates
+    }
+    
+    /// Headless Service governing the StatefulSet and giving each broker a stable DNS name
+    fn headless_service(&self, cluster: &ShazamqCluster, name: &str, namespace: &str, ports: &Ports) -> Service {
+        let mut service_ports = vec![
+            ServicePort {
+                name: Some("kafka".to_string()),
+                port: ports.kafka,
+                ..Default::default()
+            },
+        ];
+        // Per-broker metrics/admin access at <pod>.<headless>. Named "admin" rather than
+        // "metrics" so the ServiceMonitor doesn't scrape every broker twice.
+        if ports.metrics_enabled {
+            service_ports.push(ServicePort {
+                name: Some("admin".to_string()),
+                port: ports.admin,
+                ..Default::default()
+            });
+        }
+        // Brokers reach each other through the headless Service
+        if let Some(inter_broker) = ports.inter_broker {
+            service_ports.push(ServicePort {
+                name: Some("inter-broker".to_string()),
+                port: inter_broker,
+                ..Default::default()
+            });
         }
         
-        if let Some(mirror) = &cluster.spec.mirror {
-            if mirror.enabled {
-                config.push_str("[mirror]\n");
-                config.push_str("enabled = true\n\n");
-                
-                for source in &mirror.sources {
-                    config.push_str("[[mirror.sources]]\n");
-                    config.push_str(&format!("name = \"{}\"\n", source.name));
-                    config.push_str(&format!("bootstrap_servers = \"{}\"\n", source.bootstrap_servers));
-                    config.push_str(&format!("security_protocol = \"{}\"\n", source.security_protocol));
-                    config.push_str(&format!("consumer_group_id = \"{}\"\n", source.consumer_group_id));
-                    
-                    config.push_str("topic_whitelist = [");
-                    for (i, topic) in source.topic_whitelist.iter().enumerate() {
-                        if i > 0 {
-                            config.push_str(", ");
-                        }
-                        config.push_str(&format!("\"{}\"", topic));
-                    }
-                    config.push_str("]\n\n");
-                }
-            }
+        Service {
+            metadata: ObjectMeta {
+                name: Some(headless_service_name(cluster, name)),
+                namespace: Some(namespace.to_string()),
+                labels: Some(self.common_labels(name)),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                cluster_ip: Some("None".to_string()),
+                // Selects every broker group so they share one DNS domain and membership
+                selector: Some(membership_labels(name)),
+                ports: Some(service_ports),
+                // Brokers look each other up by pod name while forming quorum, before any is ready
+                publish_not_ready_addresses: Some(
+                    cluster.spec.service.as_ref().is_none_or(|s| s.publish_not_ready_addresses),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
         }
+    }
+    
+    fn client_endpoints(&self, cluster: &ShazamqCluster, name: &str, namespace: &str) -> Vec<String> {
+        let service_config = cluster.spec.service.as_ref();
+        let port = service_config.map(|s| s.port).unwrap_or(9092);
         
-        config
+        let mut endpoints = vec![format!("{}.{}.svc:{}", name, namespace, port)];
+        if let Some(hostname) = service_config.and_then(|s| s.external_hostname.as_ref()) {
+            endpoints.push(format!("{}:{}", hostname, port));
+        }
+        endpoints
     }
     
     fn common_labels(&self, name: &str) -> BTreeMap<String, String> {
@@ -465,3 +2473,689 @@ impl Reconciler {
     }
 }
 
+/// One workload's worth of brokers belonging to a cluster
+struct BrokerGroup {
+    workload_name: String,
+    config_name: String,
+    replicas: i32,
+    /// Pod labels and StatefulSet selector; must not overlap between groups
+    selector: BTreeMap<String, String>,
+    /// Brokers that replicate partitions but are never elected leader
+    follower_only: bool,
+    node_selector: Option<BTreeMap<String, String>>,
+    /// First StatefulSet ordinal, so broker ids stay unique across regions
+    ordinal_start: Option<i32>,
+    /// Config written to the ConfigMap in place of the rendered one (spec.autoRollback)
+    served_config: Option<String>,
+}
+
+impl BrokerGroup {
+    /// The primary brokers. Their selector predates read replicas and is immutable.
+    fn brokers(cluster: &ShazamqCluster, name: &str, selector: BTreeMap<String, String>) -> Self {
+        Self {
+            workload_name: name.to_string(),
+            config_name: format!("{}-config", name),
+            replicas: cluster.spec.replicas,
+            selector,
+            follower_only: false,
+            node_selector: cluster.spec.node_selector.clone(),
+            ordinal_start: None,
+            served_config: None,
+        }
+    }
+    
+    /// One group per spec.regions entry, sharing the primary brokers' ConfigMap
+    fn regions(cluster: &ShazamqCluster, name: &str, selector: &BTreeMap<String, String>) -> Vec<Self> {
+        cluster
+            .spec
+            .regions
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(i, region)| {
+                let mut region_selector = selector.clone();
+                region_selector.insert(REGION_LABEL.to_string(), region.name.clone());
+                
+                let mut node_selector = cluster.spec.node_selector.clone().unwrap_or_default();
+                if let Some(zone) = &region.zone {
+                    node_selector.insert("topology.kubernetes.io/zone".to_string(), zone.clone());
+                }
+                node_selector.extend(region.node_selector.clone().unwrap_or_default());
+                
+                Self {
+                    workload_name: format!("{}-{}", name, region.name),
+                    config_name: format!("{}-config", name),
+                    replicas: region.replicas,
+                    selector: region_selector,
+                    follower_only: false,
+                    node_selector: (!node_selector.is_empty()).then_some(node_selector),
+                    ordinal_start: Some(i as i32 * REGION_ORDINAL_STRIDE),
+                    served_config: None,
+                }
+            })
+            .collect()
+    }
+    
+    fn read_replicas(cluster: &ShazamqCluster, name: &str) -> Option<Self> {
+        let config = cluster.spec.read_replicas.as_ref().filter(|r| r.enabled)?;
+        
+        // A distinct `app` label keeps the primary selector from matching these pods
+        let mut selector = membership_labels(name);
+        selector.insert("app".to_string(), "shazamq-read-replica".to_string());
+        selector.insert("shazamq.io/role".to_string(), "read-replica".to_string());
+        
+        Some(Self {
+            workload_name: format!("{}-read", name),
+            config_name: format!("{}-read-config", name),
+            replicas: config.replicas,
+            selector,
+            follower_only: true,
+            node_selector: cluster.spec.node_selector.clone(),
+            ordinal_start: None,
+            served_config: None,
+        })
+    }
+}
+
+/// Render labels as an equality-based label selector
+fn label_selector(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Names of the optional features enabled for a broker group, in a fixed order
+fn enabled_features(cluster: &ShazamqCluster, group: &BrokerGroup) -> Vec<&'static str> {
+    let spec = &cluster.spec;
+    let security = spec.security.as_ref().filter(|s| s.enabled);
+    [
+        ("tls", security.and_then(|s| s.tls.as_ref()).is_some_and(|t| t.enabled)),
+        ("auth", security.and_then(|s| s.auth.as_ref()).is_some_and(|a| a.enabled)),
+        ("inter-broker", spec.inter_broker().is_some()),
+        ("tiered-storage", spec.tiered_storage.as_ref().is_some_and(|t| t.enabled)),
+        ("mirror", spec.mirror.as_ref().is_some_and(|m| m.enabled)),
+        ("quotas", spec.quotas.is_some()),
+        ("acls", spec.acls.as_ref().is_some_and(|a| !a.is_empty())),
+        ("read-replica", group.follower_only),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+/// Random id correlating the log lines of a single reconcile pass
+fn reconcile_id() -> String {
+    let mut bytes = [0u8; 8];
+    match ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut bytes) {
+        Ok(()) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+/// Labels carried by every broker pod of a cluster, regardless of group
+fn membership_labels(name: &str) -> BTreeMap<String, String> {
+    let mut labels = BTreeMap::new();
+    labels.insert("shazamq.io/cluster".to_string(), name.to_string());
+    labels
+}
+
+/// Translate spec.topologySpreadConstraints, selecting the group's own pods when no selector is given
+fn topology_spread_constraints(
+    cluster: &ShazamqCluster,
+    selector: &BTreeMap<String, String>,
+) -> Option<Vec<TopologySpreadConstraint>> {
+    let constraints = cluster.spec.topology_spread_constraints.as_ref()?;
+    Some(
+        constraints
+            .iter()
+            .map(|c| TopologySpreadConstraint {
+                max_skew: c.max_skew,
+                topology_key: c.topology_key.clone(),
+                when_unsatisfiable: c.when_unsatisfiable.clone(),
+                label_selector: Some(LabelSelector {
+                    match_labels: Some(c.label_selector.clone().unwrap_or_else(|| selector.clone())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect(),
+    )
+}
+
+/// Whether an error is a 404 from the API server, e.g. for a CRD that isn't installed
+fn is_not_found(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<kube::Error>(), Some(kube::Error::Api(e)) if e.code == 404)
+}
+
+fn relocate_image(image: &str, registry: &str) -> String {
+    // Like Docker, only treat the first component as a registry host if it
+    // looks like one; otherwise the image lives on Docker Hub
+    let path = match image.split_once('/') {
+        Some((host, path)) if host.contains('.') || host.contains(':') || host == "localhost" => path,
+        _ => image,
+    };
+    format!("{}/{}", registry, path)
+}
+
+fn lifecycle_handler(hook: &LifecycleHook) -> LifecycleHandler {
+    LifecycleHandler {
+        exec: hook.exec.as_ref().map(|e| ExecAction {
+            command: Some(e.command.clone()),
+        }),
+        http_get: hook.http_get.as_ref().map(|h| HTTPGetAction {
+            path: Some(h.path.clone()),
+            port: k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(h.port),
+            scheme: h.scheme.clone(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn probe(spec: &ProbeSpec, default_failure_threshold: Option<i32>) -> Probe {
+    Probe {
+        tcp_socket: spec.tcp_socket.as_ref().map(|t| TCPSocketAction {
+            port: k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(t.port),
+            ..Default::default()
+        }),
+        http_get: spec.http_get.as_ref().map(|h| HTTPGetAction {
+            path: Some(h.path.clone()),
+            port: k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(h.port),
+            scheme: h.scheme.clone(),
+            ..Default::default()
+        }),
+        initial_delay_seconds: spec.initial_delay_seconds,
+        period_seconds: spec.period_seconds,
+        timeout_seconds: spec.timeout_seconds,
+        success_threshold: spec.success_threshold,
+        failure_threshold: spec.failure_threshold.or(default_failure_threshold),
+        ..Default::default()
+    }
+}
+
+/// The conflict-retry loop behind `patch_status`, apart from the API so it runs without
+/// a cluster. `write` sends a merge patch carrying the resourceVersion it was computed
+/// from; after a 409, `latest` fetches the object and this pass's condition changes are
+/// replayed onto its conditions before the next attempt.
+async fn patch_status_with_retry<W, WriteFut, L, LatestFut>(
+    cluster: &ShazamqCluster,
+    object: &str,
+    mut status: ShazamqClusterStatus,
+    mut write: W,
+    mut latest: L,
+) -> Result<()>
+where
+    W: FnMut(serde_json::Value) -> WriteFut,
+    WriteFut: std::future::Future<Output = kube::Result<()>>,
+    L: FnMut() -> LatestFut,
+    LatestFut: std::future::Future<Output = kube::Result<ShazamqCluster>>,
+{
+    let mut base_conditions = cluster
+        .status
+        .as_ref()
+        .and_then(|s| s.conditions.clone())
+        .unwrap_or_default();
+    let mut resource_version = cluster.resource_version();
+    
+    for attempt in 1..=STATUS_PATCH_ATTEMPTS {
+        let mut patch = serde_json::json!({ "status": status });
+        if let Some(version) = &resource_version {
+            patch["metadata"] = serde_json::json!({ "resourceVersion": version });
+        }
+        
+        match write(patch).await {
+            Ok(()) => return Ok(()),
+            Err(kube::Error::Api(e)) if e.code == 409 && attempt < STATUS_PATCH_ATTEMPTS => {
+                info!(object = %object, attempt = attempt, "Status changed concurrently, retrying on the latest object");
+                let latest = latest().await?;
+                let latest_conditions = latest
+                    .status
+                    .as_ref()
+                    .and_then(|s| s.conditions.clone())
+                    .unwrap_or_default();
+                if let Some(conditions) = status.conditions.as_mut() {
+                    *conditions = rebase_conditions(&base_conditions, conditions, &latest_conditions);
+                }
+                base_conditions = latest_conditions;
+                resource_version = latest.resource_version();
+            }
+            Err(e) => return Err(e).with_context(|| format!("updating status of {} (attempt {})", object, attempt)),
+        }
+    }
+    
+    unreachable!("the last attempt always returns")
+}
+
+/// Replay the conditions that changed between `base` and `ours` onto `latest`,
+/// keeping whatever other writers changed in the meantime
+fn rebase_conditions(
+    base: &[StatusCondition],
+    ours: &[StatusCondition],
+    latest: &[StatusCondition],
+) -> Vec<StatusCondition> {
+    let mut conditions = latest.to_vec();
+    for condition in ours.iter().filter(|c| !base.contains(c)) {
+        match conditions.iter_mut().find(|c| c.r#type == condition.r#type) {
+            Some(existing) => *existing = condition.clone(),
+            None => conditions.push(condition.clone()),
+        }
+    }
+    conditions
+}
+
+/// Insert or update a condition by type, keeping lastTransitionTime unless the status flips
+fn upsert_condition(
+    conditions: &mut Vec<StatusCondition>,
+    type_: &str,
+    status: &str,
+    reason: &str,
+    message: &str,
+) {
+    let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let message = (!message.is_empty()).then(|| message.to_string());
+    
+    match conditions.iter_mut().find(|c| c.r#type == type_) {
+        Some(existing) => {
+            if existing.status != status {
+                existing.status = status.to_string();
+                existing.last_transition_time = now;
+            }
+            existing.reason = Some(reason.to_string());
+            existing.message = message;
+        }
+        None => conditions.push(StatusCondition {
+            r#type: type_.to_string(),
+            status: status.to_string(),
+            last_transition_time: now,
+            reason: Some(reason.to_string()),
+            message,
+        }),
+    }
+}
+
+/// Annotation key for a Secret's checksum. The name part of a key is capped at
+/// 63 characters, so longer Secret names are shortened with a hash suffix.
+fn secret_checksum_annotation(secret_name: &str) -> String {
+    let name = if secret_name.len() <= 63 {
+        secret_name.to_string()
+    } else {
+        let suffix = &sha256_hex(secret_name.as_bytes())[..8];
+        let prefix = secret_name[..54].trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+        format!("{}-{}", prefix, suffix)
+    };
+    format!("{}{}", SECRET_CHECKSUM_ANNOTATION_PREFIX, name)
+}
+
+/// Whether the client Service carries the metrics port (monitoring.exposeOnClientService)
+fn metrics_on_client_service(cluster: &ShazamqCluster) -> bool {
+    cluster.spec.monitoring.as_ref().is_none_or(|m| m.expose_on_client_service)
+}
+
+/// Service exposing the broker metrics/admin port
+fn metrics_service_name(cluster: &ShazamqCluster, name: &str) -> String {
+    if metrics_on_client_service(cluster) {
+        name.to_string()
+    } else {
+        format!("{}-metrics", name)
+    }
+}
+
+/// Name of the headless Service governing the StatefulSet
+fn headless_service_name(cluster: &ShazamqCluster, name: &str) -> String {
+    cluster
+        .spec
+        .service
+        .as_ref()
+        .and_then(|s| s.headless_service_name.clone())
+        .unwrap_or_else(|| format!("{}-headless", name))
+}
+
+/// `status.lastReconcileTime` for this pass. The previous value is kept until it is a
+/// steady requeue interval old: the status write would otherwise change on every pass,
+/// and its watch event would queue the next pass straight away.
+fn last_reconcile_time(previous: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> String {
+    let fresh = previous
+        .and_then(|p| chrono::DateTime::parse_from_rfc3339(p).ok())
+        .and_then(|p| (now - p.with_timezone(&chrono::Utc)).to_std().ok())
+        .is_some_and(|age| age < STEADY_REQUEUE);
+    match previous {
+        Some(previous) if fresh => previous.to_string(),
+        _ => now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    }
+}
+
+/// Broker count for a group while spec.replicas is below spec.minReplicas: the
+/// requested count, raised to the live one so the group never shrinks
+fn floor_replicas(requested: i32, live: Option<i32>) -> i32 {
+    live.map_or(requested, |live| requested.max(live))
+}
+
+/// A volume whose requested size differs from its live volumeClaimTemplate
+struct ClaimSizeChange {
+    volume: String,
+    live: String,
+    requested: String,
+    shrink: bool,
+}
+
+/// Compare the requested volume sizes with a StatefulSet's live claim templates. The data
+/// volume is only compared when spec.storage.size is set, so clusters created with another
+/// size (or adopted) aren't flagged just because the field is unset.
+fn claim_size_changes(cluster: &ShazamqCluster, templates: &[PersistentVolumeClaim]) -> Vec<ClaimSizeChange> {
+    let data_size = cluster.spec.storage.as_ref().and_then(|s| s.size.as_deref());
+    let requested = data_size
+        .map(|size| ("data", size))
+        .into_iter()
+        .chain(log_dirs(cluster).iter().map(|l| (l.name.as_str(), l.size.as_str())));
+    
+    requested
+        .filter_map(|(volume, size)| {
+            let template = templates.iter().find(|t| t.metadata.name.as_deref() == Some(volume))?;
+            let live = template
+                .spec
+                .as_ref()
+                .and_then(|s| s.resources.as_ref())
+                .and_then(|r| r.requests.as_ref())
+                .and_then(|r| r.get("storage"))?;
+            let (desired, current) = (quantity_value(size)?, quantity_value(&live.0)?);
+            (desired != current).then(|| ClaimSizeChange {
+                volume: volume.to_string(),
+                live: live.0.clone(),
+                requested: size.to_string(),
+                shrink: desired < current,
+            })
+        })
+        .collect()
+}
+
+/// Record this pass's problems in the Degraded condition; the first reason is reported
+/// and every message is kept. Cleared once there are none, and never added until needed.
+fn set_degraded(conditions: &mut Vec<StatusCondition>, problems: &[(&str, String)]) {
+    match problems.first() {
+        Some((reason, _)) => {
+            let message = problems.iter().map(|(_, m)| m.as_str()).collect::<Vec<_>>().join("; ");
+            upsert_condition(conditions, "Degraded", "True", reason, &message);
+        }
+        None if conditions.iter().any(|c| c.r#type == "Degraded") => {
+            upsert_condition(conditions, "Degraded", "False", "AsExpected", "");
+        }
+        None => {}
+    }
+}
+
+/// [`claim_size_changes`] against a live StatefulSet
+fn live_claim_size_changes(cluster: &ShazamqCluster, sts: &StatefulSet) -> Vec<ClaimSizeChange> {
+    let templates = sts.spec.as_ref().and_then(|s| s.volume_claim_templates.as_deref()).unwrap_or_default();
+    claim_size_changes(cluster, templates)
+}
+
+/// Additional log directories, each backed by its own volumeClaimTemplate
+fn log_dirs(cluster: &ShazamqCluster) -> &[LogDirSpec] {
+    config::log_dirs(&cluster.spec)
+}
+
+/// Data directory shared by the data VolumeMount and the broker's data_dir
+fn data_path(cluster: &ShazamqCluster) -> String {
+    config::data_path(&cluster.spec)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, data)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn metrics_path(cluster: &ShazamqCluster) -> String {
+    config::metrics_path(&cluster.spec)
+}
+
+/// Read a non-negative integer from the environment, falling back to `default` when unset or invalid
+fn env_u32(name: &str, default: u32) -> u32 {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::cluster;
+    
+    fn claim_template(volume: &str, size: &str) -> PersistentVolumeClaim {
+        PersistentVolumeClaim {
+            metadata: ObjectMeta {
+                name: Some(volume.to_string()),
+                ..Default::default()
+            },
+            spec: Some(PersistentVolumeClaimSpec {
+                resources: Some(K8sResourceRequirements {
+                    requests: Some(BTreeMap::from([(
+                        "storage".to_string(),
+                        k8s_openapi::apimachinery::pkg::api::resource::Quantity(size.to_string()),
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+    
+    /// A reconciler whose client points at an address nothing listens on, for
+    /// paths that must fail before any API call
+    fn offline_reconciler() -> Reconciler {
+        let config = kube::Config::new("http://127.0.0.1:9".parse().unwrap());
+        let client = Client::try_from(config).unwrap();
+        Reconciler::new(client, Arc::new(Metrics::default()), Arc::new(Integrations::default()))
+    }
+    
+    fn condition<'a>(conditions: &'a [StatusCondition], type_: &str) -> Option<&'a StatusCondition> {
+        conditions.iter().find(|c| c.r#type == type_)
+    }
+    
+    #[tokio::test]
+    async fn cluster_without_namespace_is_an_error() {
+        let mut cluster = cluster("replicas: 3\n");
+        cluster.metadata.namespace = None;
+        
+        let err = offline_reconciler().reconcile_cluster(Arc::new(cluster)).await.unwrap_err();
+        assert!(err.to_string().contains("has no namespace"), "{}", err);
+    }
+    
+    fn at(time: &str) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&chrono::Utc)
+    }
+    
+    #[test]
+    fn recent_reconcile_time_is_kept() {
+        let previous = "2026-01-01T00:00:00Z";
+        assert_eq!(last_reconcile_time(Some(previous), at("2026-01-01T00:04:59Z")), previous);
+    }
+    
+    #[test]
+    fn reconcile_time_is_refreshed_after_the_steady_interval() {
+        let now = at("2026-01-01T00:05:00Z");
+        assert_eq!(last_reconcile_time(Some("2026-01-01T00:00:00Z"), now), "2026-01-01T00:05:00Z");
+        assert_eq!(last_reconcile_time(None, now), "2026-01-01T00:05:00Z");
+        assert_eq!(last_reconcile_time(Some("garbage"), now), "2026-01-01T00:05:00Z");
+        // A timestamp in the future (clock skew) is replaced rather than kept forever
+        assert_eq!(last_reconcile_time(Some("2026-01-01T01:00:00Z"), now), "2026-01-01T00:05:00Z");
+    }
+    
+    fn headless_spec(yaml: &str) -> ServiceSpec {
+        let cluster = cluster(yaml);
+        let ports = Ports::new(&cluster.spec);
+        offline_reconciler().headless_service(&cluster, "demo", "messaging", &ports).spec.unwrap()
+    }
+    
+    #[tokio::test]
+    async fn headless_service_publishes_not_ready_addresses() {
+        let spec = headless_spec("replicas: 3\n");
+        assert_eq!(spec.publish_not_ready_addresses, Some(true));
+        assert_eq!(spec.cluster_ip.as_deref(), Some("None"));
+    }
+    
+    #[tokio::test]
+    async fn headless_service_can_hide_not_ready_addresses() {
+        let spec = headless_spec("replicas: 3\nservice:\n  publishNotReadyAddresses: false\n");
+        assert_eq!(spec.publish_not_ready_addresses, Some(false));
+    }
+    
+    fn status_condition(type_: &str, status: &str) -> StatusCondition {
+        let mut conditions = Vec::new();
+        upsert_condition(&mut conditions, type_, status, "Test", "");
+        conditions.remove(0)
+    }
+    
+    fn with_status(mut cluster: ShazamqCluster, version: &str, conditions: Vec<StatusCondition>) -> ShazamqCluster {
+        cluster.metadata.resource_version = Some(version.to_string());
+        cluster.status = Some(ShazamqClusterStatus {
+            conditions: Some(conditions),
+            ..Default::default()
+        });
+        cluster
+    }
+    
+    fn conflict() -> kube::Error {
+        kube::Error::Api(kube::core::ErrorResponse {
+            status: "Failure".to_string(),
+            message: "the object has been modified".to_string(),
+            reason: "Conflict".to_string(),
+            code: 409,
+        })
+    }
+    
+    #[tokio::test]
+    async fn status_conflict_is_retried_on_the_latest_object() {
+        let cached = with_status(cluster("replicas: 3\n"), "1", vec![status_condition("ReplicasReady", "False")]);
+        // Another writer added a condition and bumped the resourceVersion in the meantime
+        let latest = with_status(
+            cluster("replicas: 3\n"),
+            "2",
+            vec![status_condition("ReplicasReady", "False"), status_condition("Rebalancing", "True")],
+        );
+        let ours = ShazamqClusterStatus {
+            conditions: Some(vec![status_condition("ReplicasReady", "True")]),
+            ..Default::default()
+        };
+        
+        let mut patches = Vec::new();
+        let mut results = vec![Ok(()), Err(conflict())];
+        let write = |patch: serde_json::Value| {
+            patches.push(patch);
+            std::future::ready(results.pop().unwrap())
+        };
+        let fetch = || std::future::ready(Ok(latest.clone()));
+        patch_status_with_retry(&cached, "messaging/demo", ours, write, fetch).await.unwrap();
+        
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0]["metadata"]["resourceVersion"], "1");
+        assert_eq!(patches[1]["metadata"]["resourceVersion"], "2");
+        let conditions: Vec<StatusCondition> =
+            serde_json::from_value(patches[1]["status"]["conditions"].clone()).unwrap();
+        assert_eq!(condition(&conditions, "ReplicasReady").unwrap().status, "True");
+        assert_eq!(condition(&conditions, "Rebalancing").unwrap().status, "True");
+    }
+    
+    #[tokio::test]
+    async fn status_conflicts_give_up_after_the_last_attempt() {
+        let cached = with_status(cluster("replicas: 3\n"), "1", Vec::new());
+        let mut attempts = 0;
+        let write = |_| {
+            attempts += 1;
+            std::future::ready(Err(conflict()))
+        };
+        let fetch = || std::future::ready(Ok(cached.clone()));
+        let err = patch_status_with_retry(&cached, "messaging/demo", ShazamqClusterStatus::default(), write, fetch)
+            .await
+            .unwrap_err();
+        
+        assert_eq!(attempts, STATUS_PATCH_ATTEMPTS);
+        assert!(err.to_string().contains("attempt 3"), "{}", err);
+    }
+    
+    #[test]
+    fn floor_keeps_the_live_broker_count() {
+        assert_eq!(floor_replicas(2, Some(5)), 5);
+    }
+    
+    #[test]
+    fn floor_allows_growing_towards_the_request() {
+        assert_eq!(floor_replicas(4, Some(3)), 4);
+    }
+    
+    #[test]
+    fn floor_without_live_workload_keeps_the_request() {
+        assert_eq!(floor_replicas(1, None), 1);
+    }
+    
+    #[test]
+    fn data_volume_shrink_is_detected() {
+        let cluster = cluster("replicas: 3\nstorage:\n  size: 50Gi\n");
+        let changes = claim_size_changes(&cluster, &[claim_template("data", "100Gi")]);
+        
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].volume, "data");
+        assert!(changes[0].shrink);
+    }
+    
+    #[test]
+    fn data_volume_growth_is_not_a_shrink() {
+        let cluster = cluster("replicas: 3\nstorage:\n  size: 200Gi\n");
+        let changes = claim_size_changes(&cluster, &[claim_template("data", "100Gi")]);
+        
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].shrink);
+    }
+    
+    #[test]
+    fn equal_sizes_in_other_units_are_unchanged() {
+        let cluster = cluster("replicas: 3\nstorage:\n  size: 1Ti\n");
+        assert!(claim_size_changes(&cluster, &[claim_template("data", "1024Gi")]).is_empty());
+    }
+    
+    #[test]
+    fn unset_data_size_is_not_compared() {
+        let cluster = cluster("replicas: 3\n");
+        assert!(claim_size_changes(&cluster, &[claim_template("data", "500Gi")]).is_empty());
+    }
+    
+    #[test]
+    fn log_dir_shrink_is_detected() {
+        let cluster = cluster(
+            "replicas: 3\nstorage:\n  logDirs:\n    - name: logs-1\n      path: /var/lib/shazamq/logs-1\n      size: 10Gi\n",
+        );
+        let changes = claim_size_changes(&cluster, &[claim_template("data", "100Gi"), claim_template("logs-1", "20Gi")]);
+        
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].volume, "logs-1");
+        assert!(changes[0].shrink);
+    }
+    
+    #[test]
+    fn degraded_reports_first_reason_and_every_message() {
+        let mut conditions = Vec::new();
+        set_degraded(
+            &mut conditions,
+            &[("StorageShrinkRefused", "volume data".to_string()), ("Other", "second".to_string())],
+        );
+        
+        let degraded = condition(&conditions, "Degraded").unwrap();
+        assert_eq!(degraded.status, "True");
+        assert_eq!(degraded.reason.as_deref(), Some("StorageShrinkRefused"));
+        assert_eq!(degraded.message.as_deref(), Some("volume data; second"));
+    }
+    
+    #[test]
+    fn degraded_clears_once_problems_are_gone() {
+        let mut conditions = Vec::new();
+        set_degraded(&mut conditions, &[]);
+        assert!(condition(&conditions, "Degraded").is_none());
+        
+        set_degraded(&mut conditions, &[("StorageShrinkRefused", "volume data".to_string())]);
+        set_degraded(&mut conditions, &[]);
+        assert_eq!(condition(&conditions, "Degraded").unwrap().status, "False");
+    }
+}
@@ -0,0 +1,1060 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Validation - Rejects malformed ShazamqCluster specs before anything is applied
+
+use crate::config::Ports;
+use crate::crd::{LifecycleHook, ProbeSpec, QuotaLimits, ResourceList, ShazamqClusterSpec, REGION_ORDINAL_STRIDE};
+use anyhow::{bail, Result};
+
+/// Container names the operator manages in the broker pod
+const RESERVED_CONTAINER_NAMES: &[&str] = &["shazamq", "debug"];
+
+/// Env vars the operator sets on the broker container from the downward API
+const RESERVED_ENV_NAMES: &[&str] =
+    &["POD_NAME", "POD_NAMESPACE", "POD_IP", "POD_ORDINAL", "SHAZAMQ_ADVERTISED_HOST"];
+
+/// Sysctls Kubernetes allows by default; any other needs --allowed-unsafe-sysctls on the kubelet
+const SAFE_SYSCTLS: &[&str] = &[
+    "kernel.shm_rmid_forced",
+    "net.ipv4.ip_local_port_range",
+    "net.ipv4.ip_unprivileged_port_start",
+    "net.ipv4.ip_local_reserved_ports",
+    "net.ipv4.ping_group_range",
+    "net.ipv4.tcp_syncookies",
+    "net.ipv4.tcp_keepalive_time",
+    "net.ipv4.tcp_keepalive_intvl",
+    "net.ipv4.tcp_keepalive_probes",
+    "net.ipv4.tcp_fin_timeout",
+];
+
+/// Whether a sysctl is allowed by every kubelet without extra configuration
+pub fn is_safe_sysctl(name: &str) -> bool {
+    SAFE_SYSCTLS.contains(&name.replace('/', ".").as_str())
+}
+
+/// Prefixes of the sysctls isolated per pod; node-wide ones (e.g. fs.file-max) can't be set on a pod
+const NAMESPACED_SYSCTL_PREFIXES: &[&str] = &["kernel.shm", "kernel.msg", "kernel.sem", "fs.mqueue.", "net."];
+
+/// ACL resource types, operations and permissions understood by the broker
+const ACL_RESOURCE_TYPES: &[&str] = &["Topic", "Group", "Cluster", "TransactionalId"];
+const ACL_OPERATIONS: &[&str] = &[
+    "Read",
+    "Write",
+    "Create",
+    "Delete",
+    "Alter",
+    "Describe",
+    "ClusterAction",
+    "DescribeConfigs",
+    "AlterConfigs",
+    "IdempotentWrite",
+    "All",
+];
+const ACL_PERMISSIONS: &[&str] = &["Allow", "Deny"];
+
+/// Listener security protocols and SASL mechanisms understood by the broker
+const SECURITY_PROTOCOLS: &[&str] = &["PLAINTEXT", "SSL", "SASL_PLAINTEXT", "SASL_SSL"];
+const SASL_MECHANISMS: &[&str] = &["PLAIN", "SCRAM-SHA-256", "SCRAM-SHA-512"];
+
+/// Upper bounds for the broker thread pools; larger values only add contention
+const MAX_NETWORK_THREADS: i32 = 128;
+const MAX_IO_THREADS: i32 = 256;
+const MAX_REPLICA_FETCHERS: i32 = 32;
+
+/// Upper bounds for spec.networking; beyond these a typo is more likely than intent
+const MAX_CONNECTIONS: i32 = 1_000_000;
+const MAX_IDLE_TIMEOUT_SECONDS: i32 = 86_400;
+const MAX_REQUEST_TIMEOUT_SECONDS: i32 = 3_600;
+
+/// Scheduling behaviours for a topology spread constraint that can't be met
+const WHEN_UNSATISFIABLE: &[&str] = &["DoNotSchedule", "ScheduleAnyway"];
+
+/// Compression codecs supported by the broker
+const COMPRESSION_CODECS: &[&str] = &["none", "gzip", "snappy", "lz4", "zstd"];
+
+/// Object names that must be valid DNS labels (Services) are capped at 63 characters
+const MAX_SERVICE_NAME_LEN: usize = 63;
+
+/// StatefulSet pods carry a `controller-revision-hash` label of `<name>-<hash>`,
+/// which must itself fit in 63 characters
+const MAX_STATEFULSET_NAME_LEN: usize = 52;
+
+/// Reject cluster names whose derived child object names would exceed Kubernetes limits
+pub fn validate_name(name: &str, spec: &ShazamqClusterSpec) -> Result<()> {
+    let mut derived = vec![("Service", name.to_string(), MAX_SERVICE_NAME_LEN)];
+    if spec.service.as_ref().is_none_or(|s| s.create_headless && s.headless_service_name.is_none()) {
+        derived.push(("Service", format!("{}-headless", name), MAX_SERVICE_NAME_LEN));
+    }
+    if spec.monitoring.as_ref().is_some_and(|m| m.enabled && !m.expose_on_client_service) {
+        derived.push(("Service", format!("{}-metrics", name), MAX_SERVICE_NAME_LEN));
+    }
+    if spec.workload_type != "Deployment" {
+        derived.push(("StatefulSet", name.to_string(), MAX_STATEFULSET_NAME_LEN));
+    }
+    if spec.read_replicas.as_ref().is_some_and(|r| r.enabled) {
+        derived.push(("StatefulSet", format!("{}-read", name), MAX_STATEFULSET_NAME_LEN));
+    }
+    for region in spec.regions.iter().flatten() {
+        derived.push(("StatefulSet", format!("{}-{}", name, region.name), MAX_STATEFULSET_NAME_LEN));
+    }
+
+    // Longest base name for which every derived name still fits
+    let max_len = derived
+        .iter()
+        .map(|(_, child, limit)| limit - (child.len() - name.len()))
+        .min()
+        .unwrap_or(MAX_SERVICE_NAME_LEN);
+    if let Some((kind, child, limit)) = derived.iter().find(|(_, child, limit)| child.len() > *limit) {
+        bail!(
+            "metadata.name: \"{}\" is too long: {} \"{}\" would exceed {} characters; use at most {} characters",
+            name,
+            kind,
+            child,
+            limit,
+            max_len
+        );
+    }
+
+    Ok(())
+}
+
+/// Validate a ShazamqCluster spec, returning an error naming the offending field
+pub fn validate_spec(spec: &ShazamqClusterSpec) -> Result<()> {
+    if let Some(filter) = &spec.log_filter {
+        if filter.trim().is_empty() {
+            bail!("spec.logFilter: must not be empty when set");
+        }
+    }
+
+    if let Some(service) = &spec.service {
+        match service.service_type.as_str() {
+            "ClusterIP" | "NodePort" | "LoadBalancer" => {}
+            "ExternalName" => bail!(
+                "spec.service.type: ExternalName cannot be used for the client Service because it must select the broker pods"
+            ),
+            other => bail!(
+                "spec.service.type: \"{}\" must be one of ClusterIP, NodePort, LoadBalancer (case-sensitive)",
+                other
+            ),
+        }
+        if service.service_type == "ClusterIP" && service.external_hostname.is_some() {
+            bail!("spec.service.externalHostname: requires service type NodePort or LoadBalancer, not ClusterIP");
+        }
+        if let Some(headless) = &service.headless_service_name {
+            if !is_dns_label(headless) {
+                bail!("spec.service.headlessServiceName: \"{}\" is not a valid Service name", headless);
+            }
+        } else if !service.create_headless {
+            bail!("spec.service.headlessServiceName: required when createHeadless is false");
+        }
+        if let Some(hostname) = &service.external_hostname {
+            if hostname.trim().is_empty() {
+                bail!("spec.service.externalHostname: must not be empty when set");
+            }
+        }
+    }
+
+    if let Some(template) = &spec.advertised_host_template {
+        let field = "spec.advertisedHostTemplate";
+        if !template.contains("{pod}") && !template.contains("{ordinal}") {
+            bail!("{}: \"{}\" must contain {{pod}} or {{ordinal}} so every broker gets its own host", field, template);
+        }
+        if template.contains("{ordinal}") && spec.workload_type != "StatefulSet" {
+            bail!("{}: {{ordinal}} requires workloadType StatefulSet", field);
+        }
+        let sample = template.replace("{pod}", "broker-0").replace("{ordinal}", "0");
+        if !is_dns_subdomain(&sample) {
+            bail!("{}: \"{}\" does not render to a valid DNS name", field, template);
+        }
+    }
+
+    if let Some(regions) = &spec.regions {
+        if regions.is_empty() {
+            bail!("spec.regions: must not be empty when set");
+        }
+        if spec.workload_type != "StatefulSet" {
+            bail!("spec.regions: requires workloadType StatefulSet");
+        }
+        if spec.incremental_scale_up {
+            bail!("spec.regions: can't be combined with incrementalScaleUp");
+        }
+        if spec.storage.as_ref().is_some_and(|s| s.existing_claim.is_some()) {
+            bail!("spec.regions: can't be combined with storage.existingClaim");
+        }
+        let mut region_names: Vec<&str> = Vec::new();
+        for (i, region) in regions.iter().enumerate() {
+            let path = format!("spec.regions[{}]", i);
+            if !is_dns_label(&region.name) || region.name == "read" {
+                bail!("{}.name: \"{}\" must be a DNS label other than \"read\"", path, region.name);
+            }
+            if region_names.contains(&region.name.as_str()) {
+                bail!("{}.name: \"{}\" is already listed", path, region.name);
+            }
+            region_names.push(&region.name);
+            if !(1..=REGION_ORDINAL_STRIDE).contains(&region.replicas) {
+                bail!(
+                    "{}.replicas: {} must be between 1 and {}",
+                    path,
+                    region.replicas,
+                    REGION_ORDINAL_STRIDE
+                );
+            }
+            if region.zone.as_ref().is_some_and(|z| z.trim().is_empty()) {
+                bail!("{}.zone: must not be empty when set", path);
+            }
+        }
+        let total: i32 = regions.iter().map(|r| r.replicas).sum();
+        if total != spec.replicas {
+            bail!(
+                "spec.regions: replicas add up to {} but spec.replicas is {}",
+                total,
+                spec.replicas
+            );
+        }
+    }
+
+    if let Some(mirror) = &spec.mirror {
+        for (i, source) in mirror.sources.iter().enumerate() {
+            if let Some(n) = source.num_consumers.filter(|n| *n < 1) {
+                bail!("spec.mirror.sources[{}].numConsumers: {} must be at least 1", i, n);
+            }
+            if let Some(blacklist) = &source.topic_blacklist {
+                let field = format!("spec.mirror.sources[{}].topicBlacklist", i);
+                if blacklist.is_empty() {
+                    bail!("{}: must not be empty when set", field);
+                }
+                if let Some(topic) = blacklist.iter().find(|t| source.topic_whitelist.contains(t)) {
+                    bail!(
+                        "{}: \"{}\" is also listed in topicWhitelist of source \"{}\"",
+                        field,
+                        topic,
+                        source.name
+                    );
+                }
+            }
+        }
+    }
+
+    let partition = spec
+        .update_strategy
+        .as_ref()
+        .and_then(|u| u.rolling_update.as_ref())
+        .and_then(|r| r.partition);
+    if let Some(partition) = partition {
+        if !(0..=spec.replicas).contains(&partition) {
+            bail!(
+                "spec.updateStrategy.rollingUpdate.partition: {} must be between 0 and replicas ({})",
+                partition,
+                spec.replicas
+            );
+        }
+    }
+
+    if let Some(storage) = &spec.storage {
+        if !storage.data_path.starts_with('/') {
+            bail!(
+                "spec.storage.dataPath: \"{}\" must be an absolute path",
+                storage.data_path
+            );
+        }
+        if let Some(size) = &storage.size {
+            validate_quantity(size, "spec.storage.size")?;
+        }
+        let mut names: Vec<&str> = vec!["data", "config"];
+        let mut paths: Vec<&str> = vec![storage.data_path.as_str(), "/etc/shazamq"];
+        for (i, log_dir) in storage.log_dirs.iter().flatten().enumerate() {
+            let path = format!("spec.storage.logDirs[{}]", i);
+            if !is_dns_label(&log_dir.name) {
+                bail!("{}.name: \"{}\" is not a valid volume name", path, log_dir.name);
+            }
+            if names.contains(&log_dir.name.as_str()) {
+                bail!("{}.name: \"{}\" is already used by another volume", path, log_dir.name);
+            }
+            if !log_dir.path.starts_with('/') {
+                bail!("{}.path: \"{}\" must be an absolute path", path, log_dir.path);
+            }
+            if paths.contains(&log_dir.path.as_str()) {
+                bail!("{}.path: \"{}\" is already used by another volume", path, log_dir.path);
+            }
+            validate_quantity(&log_dir.size, &format!("{}.size", path))?;
+            names.push(&log_dir.name);
+            paths.push(&log_dir.path);
+        }
+        if let Some(claim) = &storage.existing_claim {
+            if !is_dns_subdomain(claim) {
+                bail!("spec.storage.existingClaim: \"{}\" is not a valid PVC name", claim);
+            }
+            // A ReadWriteOnce claim can only back a single broker
+            if spec.replicas != 1 {
+                bail!("spec.storage.existingClaim: requires replicas to be 1, got {}", spec.replicas);
+            }
+            if spec.read_replicas.as_ref().is_some_and(|r| r.enabled) {
+                bail!("spec.storage.existingClaim: cannot be combined with readReplicas");
+            }
+            if storage.ephemeral == Some(true) {
+                bail!("spec.storage.existingClaim: cannot be combined with ephemeral storage");
+            }
+            if spec.workload_type != "StatefulSet" {
+                bail!("spec.storage.existingClaim: requires workloadType StatefulSet");
+            }
+        }
+        if let Some(policy) = &storage.pvc_retention_policy {
+            for (field, value) in [("whenDeleted", &policy.when_deleted), ("whenScaled", &policy.when_scaled)] {
+                if !matches!(value.as_str(), "Retain" | "Delete") {
+                    bail!(
+                        "spec.storage.pvcRetentionPolicy.{}: \"{}\" must be Retain or Delete",
+                        field,
+                        value
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(compression) = &spec.compression {
+        if !COMPRESSION_CODECS.contains(&compression.as_str()) {
+            bail!(
+                "spec.compression: \"{}\" must be one of {}",
+                compression,
+                COMPRESSION_CODECS.join(", ")
+            );
+        }
+    }
+
+    if let Some(debug) = spec.debug.as_ref().filter(|d| d.enabled) {
+        if debug.profiler_args.as_ref().is_some_and(|a| a.is_empty()) {
+            bail!("spec.debug.profilerArgs: must not be empty when set");
+        }
+        if debug.broker_command.is_empty() {
+            bail!("spec.debug.brokerCommand: must not be empty");
+        }
+        for (i, var) in debug.env.iter().flatten().enumerate() {
+            if RESERVED_ENV_NAMES.contains(&var.name.as_str()) {
+                bail!("spec.debug.env[{}].name: \"{}\" is set by the operator", i, var.name);
+            }
+        }
+    }
+
+    let mut runtime_names: Vec<&str> = Vec::new();
+    for (i, var) in spec.runtime.iter().flat_map(|r| r.env.iter().flatten()).enumerate() {
+        let path = format!("spec.runtime.env[{}]", i);
+        let valid_name = var.name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && var.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            bail!("{}.name: \"{}\" is not a valid environment variable name", path, var.name);
+        }
+        if RESERVED_ENV_NAMES.contains(&var.name.as_str()) {
+            bail!("{}.name: \"{}\" is set by the operator", path, var.name);
+        }
+        if runtime_names.contains(&var.name.as_str()) {
+            bail!("{}.name: \"{}\" is already set", path, var.name);
+        }
+        runtime_names.push(&var.name);
+        if let Some(expected) = allocator_env_error(&var.name, &var.value) {
+            bail!("{}.value: \"{}\" is not valid for {}: {}", path, var.value, var.name, expected);
+        }
+    }
+
+    if let Some(lifecycle) = &spec.lifecycle {
+        if let Some(hook) = &lifecycle.post_start {
+            validate_lifecycle_hook(hook, "spec.lifecycle.postStart")?;
+        }
+        if let Some(hook) = &lifecycle.pre_stop {
+            validate_lifecycle_hook(hook, "spec.lifecycle.preStop")?;
+        }
+    }
+
+    let ports = Ports::new(spec);
+    if let Some(config) = &spec.ports {
+        for (field, port) in [("kafka", config.kafka), ("metrics", config.metrics)] {
+            if !(1..=65535).contains(&port) {
+                bail!("spec.ports.{}: {} is not a valid port", field, port);
+            }
+        }
+        if config.kafka == config.metrics {
+            bail!("spec.ports: kafka and metrics must use different ports");
+        }
+    }
+
+    if let Some(probes) = &spec.probes {
+        for (field, probe) in [
+            ("liveness", &probes.liveness),
+            ("readiness", &probes.readiness),
+            ("startup", &probes.startup),
+        ] {
+            if let Some(probe) = probe {
+                validate_probe(probe, &format!("spec.probes.{}", field), &ports)?;
+            }
+        }
+    }
+
+    if let Some(seconds) = spec.min_ready_seconds {
+        if seconds < 0 {
+            bail!("spec.minReadySeconds: {} must not be negative", seconds);
+        }
+    }
+
+    match spec.workload_type.as_str() {
+        "StatefulSet" => {}
+        "Deployment" => {
+            // Without tiered storage a Deployment would lose all data on every reschedule
+            if !spec.tiered_storage.as_ref().is_some_and(|t| t.enabled) {
+                bail!("spec.workloadType: Deployment requires tieredStorage to be enabled");
+            }
+            if spec.read_replicas.as_ref().is_some_and(|r| r.enabled) {
+                bail!("spec.workloadType: Deployment cannot be combined with readReplicas");
+            }
+        }
+        other => bail!("spec.workloadType: \"{}\" must be StatefulSet or Deployment", other),
+    }
+
+    match spec.profile.as_deref() {
+        None | Some("dev") => {}
+        Some("prod") if spec.replicas < 3 => {
+            bail!("spec.replicas: {} is below the minimum of 3 for the prod profile", spec.replicas)
+        }
+        Some("prod") => {}
+        Some(other) => bail!("spec.profile: \"{}\" must be dev or prod", other),
+    }
+
+    if let Some(rollback) = spec.auto_rollback().filter(|r| r.window_seconds < 1) {
+        bail!("spec.autoRollback.windowSeconds: {} must be at least 1", rollback.window_seconds);
+    }
+
+    if let Some(partitions) = spec.default_partitions.filter(|p| *p < 1) {
+        bail!("spec.defaultPartitions: {} must be at least 1", partitions);
+    }
+
+    // Otherwise a producer only finds out when its first auto-created topic can't be placed
+    if let Some(factor) = spec.topic_defaults.as_ref().and_then(|t| t.replication_factor) {
+        if !(1..=spec.replicas).contains(&factor) {
+            bail!(
+                "spec.topicDefaults.replicationFactor: {} must be between 1 and spec.replicas ({})",
+                factor,
+                spec.replicas
+            );
+        }
+        // acks=all writes need minInsyncReplicas replicas in sync, which a smaller topic never has
+        if let Some(min_insync) = spec.replication.as_ref().map(|r| r.min_insync_replicas).filter(|m| factor < *m) {
+            bail!(
+                "spec.topicDefaults.replicationFactor: {} is below spec.replication.minInsyncReplicas ({})",
+                factor,
+                min_insync
+            );
+        }
+    }
+
+    // A replicas value below the floor is not an error: the reconciler keeps the live broker count
+    if let Some(min) = spec.min_replicas.filter(|m| *m < 1) {
+        bail!("spec.minReplicas: {} must be at least 1", min);
+    }
+
+    if let Some(pdb) = spec.pod_disruption_budget.as_ref().filter(|p| p.enabled) {
+        if pdb.max_unavailable < 1 {
+            bail!(
+                "spec.podDisruptionBudget.maxUnavailable: {} must be at least 1 so brokers can be drained",
+                pdb.max_unavailable
+            );
+        }
+    }
+
+    if let Some(read_replicas) = spec.read_replicas.as_ref().filter(|r| r.enabled) {
+        if read_replicas.replicas < 1 {
+            bail!(
+                "spec.readReplicas.replicas: {} must be at least 1 when read replicas are enabled",
+                read_replicas.replicas
+            );
+        }
+    }
+
+    if let Some(anti_affinity) = &spec.anti_affinity {
+        if !is_qualified_name(&anti_affinity.topology_key) {
+            bail!(
+                "spec.antiAffinity.topologyKey: \"{}\" is not a valid label name",
+                anti_affinity.topology_key
+            );
+        }
+    }
+
+    for (i, constraint) in spec.topology_spread_constraints.iter().flatten().enumerate() {
+        let path = format!("spec.topologySpreadConstraints[{}]", i);
+        if constraint.max_skew < 1 {
+            bail!("{}.maxSkew: {} must be at least 1", path, constraint.max_skew);
+        }
+        if !is_qualified_name(&constraint.topology_key) {
+            bail!("{}.topologyKey: \"{}\" is not a valid label name", path, constraint.topology_key);
+        }
+        if !WHEN_UNSATISFIABLE.contains(&constraint.when_unsatisfiable.as_str()) {
+            bail!(
+                "{}.whenUnsatisfiable: \"{}\" must be one of {}",
+                path,
+                constraint.when_unsatisfiable,
+                WHEN_UNSATISFIABLE.join(", ")
+            );
+        }
+        for (key, value) in constraint.label_selector.iter().flatten() {
+            if !is_qualified_name(key) {
+                bail!("{}.labelSelector: \"{}\" is not a valid label name", path, key);
+            }
+            if !is_label_value(value) {
+                bail!("{}.labelSelector.{}: \"{}\" is not a valid label value", path, key, value);
+            }
+        }
+    }
+
+    if let Some(performance) = &spec.performance {
+        for (field, value, max) in [
+            ("networkThreads", performance.network_threads, MAX_NETWORK_THREADS),
+            ("ioThreads", performance.io_threads, MAX_IO_THREADS),
+            ("numReplicaFetchers", performance.num_replica_fetchers, MAX_REPLICA_FETCHERS),
+        ] {
+            if let Some(value) = value.filter(|v| !(1..=max).contains(v)) {
+                bail!("spec.performance.{}: {} must be between 1 and {}", field, value, max);
+            }
+        }
+    }
+
+    if spec.phase_debounce_seconds < 0 {
+        bail!("spec.phaseDebounceSeconds: {} must not be negative", spec.phase_debounce_seconds);
+    }
+
+    if let Some(networking) = &spec.networking {
+        for (field, value, max) in [
+            ("maxConnections", networking.max_connections, MAX_CONNECTIONS),
+            ("connectionIdleTimeoutSeconds", networking.connection_idle_timeout_seconds, MAX_IDLE_TIMEOUT_SECONDS),
+            ("requestTimeoutSeconds", networking.request_timeout_seconds, MAX_REQUEST_TIMEOUT_SECONDS),
+        ] {
+            if let Some(value) = value.filter(|v| !(1..=max).contains(v)) {
+                bail!("spec.networking.{}: {} must be between 1 and {}", field, value, max);
+            }
+        }
+    }
+
+    if let Some(quotas) = &spec.quotas {
+        validate_quota_limits(&quotas.defaults, "spec.quotas")?;
+        for (i, entry) in quotas.overrides.iter().flatten().enumerate() {
+            let path = format!("spec.quotas.overrides[{}]", i);
+            if entry.client_id.is_none() && entry.user.is_none() {
+                bail!("{}: one of clientId or user must be set", path);
+            }
+            validate_quota_limits(&entry.limits, &path)?;
+        }
+    }
+
+    if let Some(inter_broker) = spec.inter_broker() {
+        let path = "spec.security.interBroker";
+        if !SECURITY_PROTOCOLS.contains(&inter_broker.protocol.as_str()) {
+            bail!(
+                "{}.protocol: \"{}\" must be one of {}",
+                path,
+                inter_broker.protocol,
+                SECURITY_PROTOCOLS.join(", ")
+            );
+        }
+        if inter_broker.protocol.starts_with("SASL") {
+            match &inter_broker.sasl_mechanism {
+                None => bail!("{}.saslMechanism: required for protocol {}", path, inter_broker.protocol),
+                Some(m) if !SASL_MECHANISMS.contains(&m.as_str()) => {
+                    bail!("{}.saslMechanism: \"{}\" must be one of {}", path, m, SASL_MECHANISMS.join(", "))
+                }
+                Some(_) => {}
+            }
+        }
+        if inter_broker.protocol.ends_with("SSL") && spec.inter_broker_tls_secret().is_none() {
+            bail!(
+                "{}.tlsSecretName: required for protocol {} unless security.tls is enabled",
+                path,
+                inter_broker.protocol
+            );
+        }
+        if !(1..=65535).contains(&inter_broker.port) || [ports.kafka, ports.metrics].contains(&inter_broker.port) {
+            bail!(
+                "{}.port: {} must be a valid port not already used by the broker",
+                path,
+                inter_broker.port
+            );
+        }
+    }
+
+    if let Some(bundle) = spec.trust_bundle() {
+        let path = "spec.security.trustBundle";
+        if bundle.config_map_name.is_some() == bundle.secret_name.is_some() {
+            bail!("{}: exactly one of configMapName or secretName must be set", path);
+        }
+        if bundle.key.is_empty() || bundle.key.contains('/') {
+            bail!("{}.key: \"{}\" must be a non-empty key without \"/\"", path, bundle.key);
+        }
+    }
+
+    if let Some(acls) = spec.acls.as_ref().filter(|a| !a.is_empty()) {
+        let auth_enabled = spec
+            .security
+            .as_ref()
+            .is_some_and(|s| s.enabled && s.auth.as_ref().is_some_and(|a| a.enabled));
+        if !auth_enabled {
+            bail!("spec.acls: requires security.auth to be enabled");
+        }
+        for (i, acl) in acls.iter().enumerate() {
+            let path = format!("spec.acls[{}]", i);
+            if acl.principal.trim().is_empty() {
+                bail!("{}.principal: must not be empty", path);
+            }
+            if acl.resource_name.trim().is_empty() {
+                bail!("{}.resourceName: must not be empty", path);
+            }
+            for (field, value, allowed) in [
+                ("resourceType", &acl.resource_type, ACL_RESOURCE_TYPES),
+                ("operation", &acl.operation, ACL_OPERATIONS),
+                ("permission", &acl.permission, ACL_PERMISSIONS),
+            ] {
+                if !allowed.contains(&value.as_str()) {
+                    bail!("{}.{}: \"{}\" must be one of {}", path, field, value, allowed.join(", "));
+                }
+            }
+        }
+    }
+
+    let mut sysctl_names: Vec<String> = Vec::new();
+    for (i, sysctl) in spec.sysctls.iter().flatten().enumerate() {
+        let path = format!("spec.sysctls[{}].name", i);
+        let valid_name = !sysctl.name.is_empty()
+            && sysctl.name.len() <= 253
+            && sysctl.name.split(['.', '/']).all(|segment| {
+                !segment.is_empty()
+                    && segment.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'))
+                    && segment.starts_with(|c: char| c.is_ascii_alphanumeric())
+                    && segment.ends_with(|c: char| c.is_ascii_alphanumeric())
+            });
+        if !valid_name {
+            bail!("{}: \"{}\" is not a valid sysctl name", path, sysctl.name);
+        }
+        let normalized = sysctl.name.replace('/', ".");
+        if !NAMESPACED_SYSCTL_PREFIXES.iter().any(|p| normalized.starts_with(p)) {
+            bail!("{}: \"{}\" is not namespaced and can't be set per pod", path, sysctl.name);
+        }
+        if sysctl_names.contains(&normalized) {
+            bail!("{}: \"{}\" is already set", path, sysctl.name);
+        }
+        sysctl_names.push(normalized);
+    }
+
+    let mut patterns: Vec<&str> = Vec::new();
+    for (i, retention) in spec.retention_overrides.iter().flatten().enumerate() {
+        let path = format!("spec.retentionOverrides[{}]", i);
+        if retention.pattern.trim().is_empty() {
+            bail!("{}.pattern: must not be empty", path);
+        }
+        if patterns.contains(&retention.pattern.as_str()) {
+            bail!("{}.pattern: \"{}\" is already listed", path, retention.pattern);
+        }
+        patterns.push(&retention.pattern);
+        if retention.retention_hours.is_none() && retention.retention_bytes.is_none() {
+            bail!("{}: one of retentionHours or retentionBytes must be set", path);
+        }
+        if let Some(hours) = retention.retention_hours.filter(|h| *h < 0) {
+            bail!("{}.retentionHours: {} must not be negative", path, hours);
+        }
+        if let Some(bytes) = retention.retention_bytes.filter(|b| *b < 0) {
+            bail!("{}.retentionBytes: {} must not be negative", path, bytes);
+        }
+    }
+
+    let mut container_names: Vec<&str> = RESERVED_CONTAINER_NAMES.to_vec();
+    for (i, init) in spec.init_containers.iter().flatten().enumerate() {
+        let field = format!("spec.initContainers[{}].name", i);
+        if !is_dns_label(&init.name) {
+            bail!("{}: \"{}\" is not a valid container name", field, init.name);
+        }
+        if container_names.contains(&init.name.as_str()) {
+            bail!(
+                "{}: \"{}\" collides with another container in the broker pod",
+                field,
+                init.name
+            );
+        }
+        container_names.push(&init.name);
+    }
+
+    if let Some(monitoring) = &spec.monitoring {
+        if !monitoring.path.starts_with('/') {
+            bail!("spec.monitoring.path: \"{}\" must start with '/'", monitoring.path);
+        }
+    }
+    
+    // The admin API shares the metrics listener, so everything that calls it needs metrics on
+    if !spec.metrics_enabled() {
+        for (field, needs_admin) in [
+            ("spec.waitForQuorum", spec.wait_for_quorum),
+            ("spec.rebalanceOnScale", spec.rebalance_on_scale),
+            ("spec.ingress", spec.ingress.as_ref().is_some_and(|i| i.enabled)),
+            ("spec.gatewayRoute", spec.gateway_route.as_ref().is_some_and(|r| r.enabled)),
+        ] {
+            if needs_admin {
+                bail!("{}: requires the broker admin API, which is off while spec.monitoring.enabled is false", field);
+            }
+        }
+    }
+
+    if let Some(route) = spec.gateway_route.as_ref().filter(|r| r.enabled) {
+        if route.parent_refs.is_empty() {
+            bail!("spec.gatewayRoute.parentRefs: at least one Gateway is required");
+        }
+        if route.allowed_paths.is_empty() {
+            bail!("spec.gatewayRoute.allowedPaths: must not be empty");
+        }
+        for (i, path) in route.allowed_paths.iter().enumerate() {
+            if !path.starts_with('/') {
+                bail!("spec.gatewayRoute.allowedPaths[{}]: \"{}\" must start with '/'", i, path);
+            }
+        }
+    }
+
+    if let Some(ingress) = spec.ingress.as_ref().filter(|i| i.enabled) {
+        if ingress.host.trim().is_empty() {
+            bail!("spec.ingress.host: must not be empty when the ingress is enabled");
+        }
+        if !ingress.path.starts_with('/') {
+            bail!("spec.ingress.path: \"{}\" must start with '/'", ingress.path);
+        }
+    }
+
+    if let Some(resources) = &spec.resources {
+        if let Some(requests) = &resources.requests {
+            validate_resource_list(requests, "spec.resources.requests")?;
+        }
+        if let Some(limits) = &resources.limits {
+            validate_resource_list(limits, "spec.resources.limits")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_lifecycle_hook(hook: &LifecycleHook, path: &str) -> Result<()> {
+    match (&hook.exec, &hook.http_get) {
+        (Some(_), Some(_)) => bail!("{}: exec and httpGet are mutually exclusive", path),
+        (None, None) => bail!("{}: one of exec or httpGet must be set", path),
+        (Some(exec), None) if exec.command.is_empty() => {
+            bail!("{}.exec.command: must not be empty", path)
+        }
+        (None, Some(http)) if !(1..=65535).contains(&http.port) => {
+            bail!("{}.httpGet.port: {} is not a valid port", path, http.port)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_probe(probe: &ProbeSpec, path: &str, ports: &Ports) -> Result<()> {
+    let (field, port) = match (&probe.tcp_socket, &probe.http_get) {
+        (Some(_), Some(_)) => bail!("{}: tcpSocket and httpGet are mutually exclusive", path),
+        (None, None) => bail!("{}: one of tcpSocket or httpGet must be set", path),
+        (Some(tcp), None) => ("tcpSocket", tcp.port),
+        (None, Some(http)) => {
+            if !http.path.starts_with('/') {
+                bail!("{}.httpGet.path: \"{}\" must start with \"/\"", path, http.path);
+            }
+            ("httpGet", http.port)
+        }
+    };
+    let container_ports = ports.container_ports();
+    if !container_ports.contains(&port) {
+        bail!(
+            "{}.{}.port: {} is not declared on the broker container (expected one of {})",
+            path,
+            field,
+            port,
+            container_ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+        );
+    }
+    if let Some(delay) = probe.initial_delay_seconds.filter(|d| *d < 0) {
+        bail!("{}.initialDelaySeconds: {} must not be negative", path, delay);
+    }
+    for (field, value) in [
+        ("periodSeconds", probe.period_seconds),
+        ("timeoutSeconds", probe.timeout_seconds),
+        ("successThreshold", probe.success_threshold),
+        ("failureThreshold", probe.failure_threshold),
+    ] {
+        if let Some(value) = value.filter(|v| *v < 1) {
+            bail!("{}.{}: {} must be at least 1", path, field, value);
+        }
+    }
+    // Kubernetes only allows a successThreshold above 1 on readiness probes
+    if !path.ends_with(".readiness") {
+        if let Some(threshold) = probe.success_threshold.filter(|t| *t != 1) {
+            bail!("{}.successThreshold: {} must be 1 for liveness and startup probes", path, threshold);
+        }
+    }
+    Ok(())
+}
+
+fn validate_quota_limits(limits: &QuotaLimits, path: &str) -> Result<()> {
+    if let Some(rate) = limits.producer_byte_rate.filter(|r| *r <= 0) {
+        bail!("{}.producerByteRate: {} must be positive", path, rate);
+    }
+    if let Some(rate) = limits.consumer_byte_rate.filter(|r| *r <= 0) {
+        bail!("{}.consumerByteRate: {} must be positive", path, rate);
+    }
+    if let Some(pct) = limits.request_percentage.filter(|p| !p.is_finite() || *p <= 0.0) {
+        bail!("{}.requestPercentage: {} must be positive", path, pct);
+    }
+    Ok(())
+}
+
+fn validate_resource_list(list: &ResourceList, path: &str) -> Result<()> {
+    if let Some(cpu) = &list.cpu {
+        validate_quantity(cpu, &format!("{}.cpu", path))?;
+    }
+    if let Some(memory) = &list.memory {
+        validate_quantity(memory, &format!("{}.memory", path))?;
+    }
+    Ok(())
+}
+
+/// Check the value of a well-known allocator variable; unknown names are passed through unchecked
+fn allocator_env_error(name: &str, value: &str) -> Option<&'static str> {
+    match name {
+        "MALLOC_ARENA_MAX" => match value.parse::<u64>() {
+            Ok(n) if n > 0 => None,
+            _ => Some("expected a positive integer"),
+        },
+        "MALLOC_TRIM_THRESHOLD_" | "MALLOC_MMAP_THRESHOLD_" | "MALLOC_TOP_PAD_" => {
+            value.parse::<u64>().is_err().then_some("expected a byte count")
+        }
+        // jemalloc option string, e.g. "background_thread:true,dirty_decay_ms:1000"
+        "MALLOC_CONF" | "_RJEM_MALLOC_CONF" => {
+            let well_formed = value.split(',').all(|option| {
+                option
+                    .split_once(':')
+                    .is_some_and(|(key, setting)| !key.is_empty() && !setting.is_empty())
+            });
+            (!well_formed).then_some("expected comma-separated key:value options")
+        }
+        _ if name.starts_with("MIMALLOC_") => value.is_empty().then_some("expected a non-empty value"),
+        _ => None,
+    }
+}
+
+fn validate_quantity(value: &str, field: &str) -> Result<()> {
+    if !is_valid_quantity(value) {
+        bail!(
+            "{}: \"{}\" is not a valid Kubernetes quantity (expected e.g. \"500m\", \"2\", \"4Gi\")",
+            field,
+            value
+        );
+    }
+    Ok(())
+}
+
+/// Numeric value of a Kubernetes quantity, for comparing sizes; None when malformed
+pub fn quantity_value(value: &str) -> Option<f64> {
+    if !is_valid_quantity(value) {
+        return None;
+    }
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let number_len = unsigned
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(unsigned.len());
+    let (number, suffix) = value.split_at(value.len() - unsigned.len() + number_len);
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 2f64.powi(10),
+        "Mi" => 2f64.powi(20),
+        "Gi" => 2f64.powi(30),
+        "Ti" => 2f64.powi(40),
+        "Pi" => 2f64.powi(50),
+        "Ei" => 2f64.powi(60),
+        exponent => 10f64.powi(exponent[1..].parse().ok()?),
+    };
+    Some(number.parse::<f64>().ok()? * multiplier)
+}
+
+/// Check a string against the Kubernetes Quantity grammar:
+/// `<sign><digits>[.<digits>]<suffix>` where suffix is a binary SI unit
+/// (Ki, Mi, ...), a decimal SI unit (n, u, m, k, M, ...) or a decimal exponent (e3, E-2).
+fn is_valid_quantity(value: &str) -> bool {
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+
+    let number_len = unsigned
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(unsigned.len());
+    let (number, suffix) = unsigned.split_at(number_len);
+
+    let mut parts = number.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next();
+    if whole.is_empty() && fraction.is_none_or(str::is_empty) {
+        return false;
+    }
+    if fraction.is_some_and(|f| f.contains('.')) {
+        return false;
+    }
+
+    match suffix {
+        "" | "n" | "u" | "m" | "k" | "M" | "G" | "T" | "P" | "E" => true,
+        "Ki" | "Mi" | "Gi" | "Ti" | "Pi" | "Ei" => true,
+        _ => {
+            let Some(exponent) = suffix.strip_prefix(['e', 'E']) else {
+                return false;
+            };
+            let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+        }
+    }
+}
+
+/// Check a label key: an optional DNS subdomain prefix and a slash, followed by
+/// a name of at most 63 alphanumerics, '-', '_' or '.', starting and ending alphanumeric
+fn is_qualified_name(value: &str) -> bool {
+    let (prefix, name) = match value.rsplit_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, value),
+    };
+
+    if let Some(prefix) = prefix {
+        let valid_prefix = !prefix.is_empty()
+            && prefix.len() <= 253
+            && prefix.split('.').all(is_dns_label);
+        if !valid_prefix {
+            return false;
+        }
+    }
+
+    !name.is_empty()
+        && name.len() <= 63
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+}
+
+/// Check a label value: empty, or at most 63 alphanumerics, '-', '_' or '.',
+/// starting and ending alphanumeric
+fn is_label_value(value: &str) -> bool {
+    value.is_empty() || is_qualified_name(value) && !value.contains('/')
+}
+
+/// Check an RFC 1123 label: at most 63 lowercase alphanumerics or '-',
+/// starting and ending alphanumeric
+fn is_dns_label(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 63
+        && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !value.starts_with('-')
+        && !value.ends_with('-')
+}
+
+/// Check an RFC 1123 subdomain (the format of most object names): at most 253
+/// characters of dot-separated DNS labels
+fn is_dns_subdomain(value: &str) -> bool {
+    value.len() <= 253 && value.split('.').all(is_dns_label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crd::testing::spec;
+
+    fn name_error(length: usize, yaml: &str) -> Option<String> {
+        validate_name(&"a".repeat(length), &spec(yaml)).err().map(|e| e.to_string())
+    }
+
+    #[test]
+    fn statefulset_names_stop_at_52_characters() {
+        assert_eq!(name_error(52, "replicas: 3\n"), None);
+        let err = name_error(53, "replicas: 3\n").unwrap();
+        assert!(err.contains("StatefulSet") && err.contains("use at most 52 characters"), "{}", err);
+    }
+
+    #[test]
+    fn service_names_stop_at_63_characters() {
+        let yaml = "replicas: 3\nworkloadType: Deployment\nservice:\n  createHeadless: false\n";
+        assert_eq!(name_error(63, yaml), None);
+        let err = name_error(64, yaml).unwrap();
+        assert!(err.contains("Service") && err.contains("use at most 63 characters"), "{}", err);
+    }
+
+    #[test]
+    fn headless_service_suffix_counts_against_the_service_limit() {
+        let yaml = "replicas: 3\nworkloadType: Deployment\n";
+        assert_eq!(name_error(54, yaml), None);
+        let err = name_error(55, yaml).unwrap();
+        assert!(err.contains("-headless\" would exceed 63 characters; use at most 54"), "{}", err);
+    }
+
+    #[test]
+    fn enabled_ingress_needs_a_host() {
+        let err = validate_spec(&spec("replicas: 3\ningress:\n  enabled: true\n")).unwrap_err();
+        assert!(err.to_string().starts_with("spec.ingress.host:"), "{}", err);
+        assert!(validate_spec(&spec("replicas: 3\ningress:\n  enabled: false\n")).is_ok());
+    }
+
+    #[test]
+    fn unknown_service_type_lists_only_usable_types() {
+        let err = validate_spec(&spec("replicas: 3\nservice:\n  type: Headless\n")).unwrap_err().to_string();
+        assert!(err.contains("must be one of ClusterIP, NodePort, LoadBalancer (case-sensitive)"), "{}", err);
+        assert!(!err.contains("ExternalName"), "{}", err);
+    }
+
+    fn topic_defaults_error(yaml: &str) -> Option<String> {
+        validate_spec(&spec(yaml)).err().map(|e| e.to_string())
+    }
+
+    #[test]
+    fn replication_factor_may_equal_the_broker_count() {
+        assert_eq!(topic_defaults_error("replicas: 3\ntopicDefaults:\n  replicationFactor: 3\n"), None);
+    }
+
+    #[test]
+    fn replication_factor_above_the_broker_count_is_rejected() {
+        let err = topic_defaults_error("replicas: 3\ntopicDefaults:\n  replicationFactor: 4\n").unwrap();
+        assert!(err.contains("must be between 1 and spec.replicas (3)"), "{}", err);
+    }
+
+    #[test]
+    fn replication_factor_must_be_positive() {
+        assert!(topic_defaults_error("replicas: 3\ntopicDefaults:\n  replicationFactor: 0\n").is_some());
+        assert_eq!(topic_defaults_error("replicas: 1\ntopicDefaults:\n  replicationFactor: 1\n"), None);
+    }
+
+    #[test]
+    fn replication_factor_may_equal_min_insync_replicas() {
+        let yaml = "replicas: 3\nreplication:\n  minInsyncReplicas: 2\ntopicDefaults:\n  replicationFactor: 2\n";
+        assert_eq!(topic_defaults_error(yaml), None);
+    }
+
+    #[test]
+    fn replication_factor_below_min_insync_replicas_is_rejected() {
+        let yaml = "replicas: 3\nreplication:\n  minInsyncReplicas: 2\ntopicDefaults:\n  replicationFactor: 1\n";
+        let err = topic_defaults_error(yaml).unwrap();
+        assert!(err.contains("below spec.replication.minInsyncReplicas (2)"), "{}", err);
+    }
+
+    #[test]
+    fn existing_replication_blocks_stay_valid_on_small_clusters() {
+        // Serde fills 3/2 into an empty block; those were never checked against replicas
+        assert_eq!(topic_defaults_error("replicas: 1\nreplication: {}\n"), None);
+    }
+
+    #[test]
+    fn replicas_below_min_replicas_is_not_rejected() {
+        // The reconciler refuses the scale-down instead, so the rest of the cluster keeps reconciling
+        assert!(validate_spec(&spec("replicas: 2\nminReplicas: 3\n")).is_ok());
+    }
+
+    #[test]
+    fn min_replicas_must_be_positive() {
+        let err = validate_spec(&spec("replicas: 3\nminReplicas: 0\n")).unwrap_err();
+        assert!(err.to_string().starts_with("spec.minReplicas"));
+        assert!(validate_spec(&spec("replicas: 3\nminReplicas: 1\n")).is_ok());
+    }
+}
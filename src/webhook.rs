@@ -0,0 +1,199 @@
+// Copyright (c) 2025 Murtaza Shajapurwala
+//
+// Webhook - Mutating admission webhook that persists spec defaults
+
+use crate::crd::ShazamqClusterSpec;
+use anyhow::{bail, Context, Result};
+use hyper::server::conn::Http;
+use hyper::service::service_fn;
+use hyper::{body, Body, Method, Request, Response, StatusCode};
+use kube::core::admission::{AdmissionRequest, AdmissionResponse, AdmissionReview};
+use kube::core::DynamicObject;
+use serde_json::Value;
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, error, info, warn};
+
+/// Fill serde defaults and the image/tag split into a raw spec.
+///
+/// Profile defaults are left to the reconciler: once stored they could not be
+/// told apart from explicit settings, so changing `spec.profile` would keep the
+/// old profile's values.
+pub fn default_spec(raw: &Value) -> Result<Value> {
+    let mut spec: ShazamqClusterSpec = serde_json::from_value(raw.clone())?;
+    spec.normalize_image();
+    let mut defaulted = serde_json::to_value(&spec)?;
+    strip_nulls(&mut defaulted);
+    Ok(defaulted)
+}
+
+/// Unset optional fields serialize as null; leave them out of the stored spec
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Serve `POST /mutate` over TLS until the process exits.
+///
+/// `cert_dir` must contain `tls.crt` and `tls.key`, as mounted from a
+/// `kubernetes.io/tls` Secret.
+pub async fn serve(port: u16, cert_dir: PathBuf) {
+    if let Err(e) = run(port, &cert_dir).await {
+        error!(error = %e, "Admission webhook failed");
+    }
+}
+
+async fn run(port: u16, cert_dir: &Path) -> Result<()> {
+    let acceptor = TlsAcceptor::from(Arc::new(tls_config(cert_dir)?));
+    let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port))).await?;
+
+    info!(port = port, "Serving mutating admission webhook");
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let acceptor = acceptor.clone();
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    debug!(peer = %peer, error = %e, "Webhook TLS handshake failed");
+                    return;
+                }
+            };
+            if let Err(e) = Http::new().serve_connection(stream, service_fn(handle)).await {
+                debug!(peer = %peer, error = %e, "Webhook connection failed");
+            }
+        });
+    }
+}
+
+fn tls_config(cert_dir: &Path) -> Result<ServerConfig> {
+    let cert_path = cert_dir.join("tls.crt");
+    let key_path = cert_dir.join("tls.key");
+
+    let certs = rustls_pemfile::certs(&mut BufReader::new(
+        File::open(&cert_path).with_context(|| format!("opening {}", cert_path.display()))?,
+    ))?
+    .into_iter()
+    .map(Certificate)
+    .collect();
+
+    let mut key_reader = BufReader::new(
+        File::open(&key_path).with_context(|| format!("opening {}", key_path.display()))?,
+    );
+    let key = loop {
+        match rustls_pemfile::read_one(&mut key_reader)? {
+            Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => break PrivateKey(key),
+            Some(_) => continue,
+            None => bail!("no private key found in {}", key_path.display()),
+        }
+    };
+
+    Ok(ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?)
+}
+
+async fn handle(req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST || req.uri().path() != "/mutate" {
+        return Ok(status_response(StatusCode::NOT_FOUND, "not found"));
+    }
+
+    let bytes = match body::to_bytes(req.into_body()).await {
+        Ok(bytes) => bytes,
+        Err(e) => return Ok(status_response(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+    let review: AdmissionReview<DynamicObject> = match serde_json::from_slice(&bytes) {
+        Ok(review) => review,
+        Err(e) => return Ok(status_response(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+    let request: AdmissionRequest<DynamicObject> = match review.try_into() {
+        Ok(request) => request,
+        Err(e) => return Ok(status_response(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+
+    let review = mutate(&request).into_review();
+    let body = serde_json::to_vec(&review).unwrap_or_default();
+    Ok(Response::new(Body::from(body)))
+}
+
+fn mutate(request: &AdmissionRequest<DynamicObject>) -> AdmissionResponse {
+    let response = AdmissionResponse::from(request);
+    let Some(raw) = request.object.as_ref().and_then(|o| o.data.get("spec")) else {
+        return response;
+    };
+
+    // A spec the operator can't parse is left for schema validation to reject
+    let defaulted = match default_spec(raw) {
+        Ok(spec) => spec,
+        Err(e) => {
+            warn!(name = %request.name, error = %e, "Skipping defaulting of unparseable spec");
+            return response;
+        }
+    };
+
+    let patch = json_patch::diff(
+        &serde_json::json!({ "spec": raw }),
+        &serde_json::json!({ "spec": defaulted }),
+    );
+    match response.with_patch(patch) {
+        Ok(response) => response,
+        Err(e) => AdmissionResponse::from(request).deny(e.to_string()),
+    }
+}
+
+fn status_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn serde_defaults_are_filled() {
+        let spec = default_spec(&json!({ "replicas": 3 })).unwrap();
+        assert_eq!(spec["replicas"], 3);
+        assert_eq!(spec["workloadType"], "StatefulSet");
+        assert!(spec["version"].is_string(), "{}", spec);
+        assert!(spec.as_object().unwrap().values().all(|v| !v.is_null()), "{}", spec);
+    }
+
+    #[test]
+    fn image_tag_moves_into_version() {
+        let spec = default_spec(&json!({ "replicas": 3, "image": "quay.io/shazamq/shazamq:0.2.0" })).unwrap();
+        assert_eq!(spec["image"], "quay.io/shazamq/shazamq");
+        assert_eq!(spec["version"], "0.2.0");
+    }
+
+    #[test]
+    fn profile_defaults_are_not_persisted() {
+        let dev = default_spec(&json!({ "replicas": 1, "profile": "dev" })).unwrap();
+        assert_eq!(dev["profile"], "dev");
+        assert!(dev.get("storage").is_none(), "{}", dev);
+        assert!(dev.get("replication").is_none(), "{}", dev);
+        
+        let prod = default_spec(&json!({ "replicas": 3, "profile": "prod" })).unwrap();
+        assert!(prod.get("antiAffinity").is_none(), "{}", prod);
+        assert!(prod.get("podDisruptionBudget").is_none(), "{}", prod);
+    }
+}